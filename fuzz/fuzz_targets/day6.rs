@@ -0,0 +1,13 @@
+#![no_main]
+
+use aoc2022::{day6::Day6, Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(parsed) = Day6::parse(input) {
+            let _ = Day6::part1(&parsed);
+            let _ = Day6::part2(&parsed);
+        }
+    }
+});
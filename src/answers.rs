@@ -0,0 +1,246 @@
+//! The answers that are known to be right, stored in `answers.toml`, with a
+//! table for every dataset of a day:
+//!
+//! ```toml
+//! # Comments and empty lines are ignored.
+//! [day7.example]
+//! part1 = 95437
+//! part2 = 24933642
+//!
+//! [day5.example]
+//! part1 = "CMZ"
+//!
+//! [day10.example]
+//! part2 = """
+//! ##..##..
+//! ###...##"""
+//! ```
+
+use std::{collections::BTreeMap, fs, ops::Range, path::Path};
+
+use toml::{
+    de::{DeTable, DeValue},
+    Spanned, Table, Value,
+};
+
+use crate::{solution::Answer, ParseError, Part};
+
+/// The file with the answers used when none is given.
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+/// The known answers for a single dataset of a day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entry {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+impl Entry {
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: Answer) {
+        match part {
+            Part::One => self.part1 = Some(answer),
+            Part::Two => self.part2 = Some(answer),
+        }
+    }
+}
+
+/// The known answers, by day and dataset name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers(pub BTreeMap<(u8, String), Entry>);
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+        Self::try_from(content.as_str())
+            .map_err(|error| format!("Invalid answers in {}: {error}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_toml())
+            .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, dataset: &str, part: Part) -> Option<&Answer> {
+        self.0
+            .get(&(day, dataset.to_string()))
+            .and_then(|entry| entry.get(part))
+    }
+
+    pub fn set(&mut self, day: u8, dataset: &str, part: Part, answer: Answer) {
+        self.0
+            .entry((day, dataset.to_string()))
+            .or_default()
+            .set(part, answer);
+    }
+
+    /// The answers in the format of `answers.toml`.
+    pub fn to_toml(&self) -> String {
+        let mut days = Table::new();
+        for ((day, dataset), entry) in &self.0 {
+            let mut parts = Table::new();
+            for part in Part::ALL {
+                if let Some(answer) = entry.get(part) {
+                    let value = match answer {
+                        Answer::Number(number) => Value::Integer(*number),
+                        Answer::Text(text) => Value::String(text.clone()),
+                    };
+                    parts.insert(format!("part{part}"), value);
+                }
+            }
+            if let Value::Table(datasets) = days
+                .entry(format!("day{day}"))
+                .or_insert_with(|| Value::Table(Table::new()))
+            {
+                datasets.insert(dataset.clone(), Value::Table(parts));
+            }
+        }
+        days.to_string()
+    }
+}
+
+fn is_key_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '-' || char == '_'
+}
+
+/// An error for the part of the input at `span`.
+fn error_at(input: &str, span: Range<usize>, expected: &str) -> String {
+    let before = &input[..span.start];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    let text = input[span].lines().next().unwrap_or_default();
+    ParseError::new(line, column, text, expected).to_string()
+}
+
+/// The value as a table, which it is `expected` to be.
+fn table<'a, 'i>(
+    input: &str,
+    value: &'a Spanned<DeValue<'i>>,
+    expected: &str,
+) -> Result<&'a DeTable<'i>, String> {
+    match value.get_ref() {
+        DeValue::Table(table) => Ok(table),
+        _ => Err(error_at(input, value.span(), expected)),
+    }
+}
+
+impl TryFrom<&str> for Answers {
+    type Error = String;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let days = DeTable::parse(input).map_err(|error| error.to_string())?;
+        let mut answers = Answers::default();
+        for (day_key, datasets) in days.get_ref() {
+            let day = day_key
+                .get_ref()
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| error_at(input, day_key.span(), "a day like day7"))?;
+            for (dataset_key, entry) in table(input, datasets, "a table like [day7.example]")? {
+                let dataset = dataset_key.get_ref();
+                if dataset.is_empty() || !dataset.chars().all(is_key_char) {
+                    return Err(error_at(
+                        input,
+                        dataset_key.span(),
+                        "a dataset name of letters, digits, - and _",
+                    ));
+                }
+                answers.0.entry((day, dataset.to_string())).or_default();
+                for (key, value) in table(input, entry, "a table of answers")? {
+                    let part = match key.get_ref().as_ref() {
+                        "part1" => Part::One,
+                        "part2" => Part::Two,
+                        _ => return Err(error_at(input, key.span(), "part1 or part2")),
+                    };
+                    let answer = match value.get_ref() {
+                        DeValue::Integer(integer) => {
+                            i64::from_str_radix(integer.as_str(), integer.radix())
+                                .map(Answer::Number)
+                                .map_err(|_| {
+                                    error_at(input, value.span(), "a number that fits in 64 bits")
+                                })?
+                        }
+                        DeValue::String(text) => Answer::Text(text.to_string()),
+                        _ => return Err(error_at(input, value.span(), "a number or a string")),
+                    };
+                    answers.set(day, dataset, part, answer);
+                }
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers::default();
+        answers.set(5, "example", Part::One, Answer::Text("CMZ".to_string()));
+        answers.set(7, "example", Part::Two, Answer::Number(24933642));
+        answers.set(
+            10,
+            "example",
+            Part::Two,
+            Answer::Text("##..\n###.\n\"#\"".to_string()),
+        );
+        answers.set(
+            10,
+            "real",
+            Part::One,
+            Answer::Text("a\\b\n\"\"\" '\'\' \"\"\"\"\n".to_string()),
+        );
+        answers.set(
+            6,
+            "real",
+            Part::One,
+            Answer::Text("a \"quoted\" \\".to_string()),
+        );
+
+        let toml = answers.to_toml();
+        assert!(toml.starts_with("[day5.example]\n"), "{toml}");
+        assert_eq!(Answers::try_from(toml.as_str()), Ok(answers));
+    }
+
+    #[test]
+    fn invalid_answers() {
+        let error = Answers::try_from("[day7.example]\npart3 = 5").unwrap_err();
+        assert!(
+            error.starts_with("line 2, column 1: expected part1 or part2"),
+            "{error}"
+        );
+        let error = Answers::try_from("part1 = 5").unwrap_err();
+        assert!(
+            error.starts_with("line 1, column 1: expected a day"),
+            "{error}"
+        );
+        let error = Answers::try_from("[day7]\npart1 = 5").unwrap_err();
+        assert!(
+            error.starts_with("line 2, column 9: expected a table"),
+            "{error}"
+        );
+        let error = Answers::try_from("[day7.\"../x\"]\npart1 = 5").unwrap_err();
+        assert!(
+            error.starts_with("line 1, column 7: expected a dataset"),
+            "{error}"
+        );
+        let error = Answers::try_from("[day10.example]\npart2 = \"\"\"\n##..").unwrap_err();
+        assert!(error.contains("line 3"), "{error}");
+    }
+}
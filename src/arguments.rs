@@ -0,0 +1,835 @@
+use std::{env, path::PathBuf};
+
+use crate::{
+    bench::{DEFAULT_ITERATIONS, DEFAULT_THRESHOLD},
+    image::DEFAULT_SCALE,
+    input::{Input, DEFAULT_DATASET},
+    logging::{Filter, LOG_VARIABLE},
+    solution::Part,
+    visualize::DEFAULT_FPS,
+};
+
+pub const USAGE: &str = "Usage:
+    aoc run --day <day> [--part <part>] [--input <path> | --dataset <name>]
+            [--stream]
+    aoc run --all [--part <part>] [--dataset <name>] [--jobs <count>]
+    aoc verify [--day <day>] [--dataset <name>] [--answers <path>]
+    aoc fetch (--day <day> | --all)
+    aoc submit --day <day> --part <part> [--input <path> | --dataset <name>]
+               [--answer <answer>] [--history <path>]
+    aoc gen --day <day> [--seed <seed>] [--size <size>] [--dataset <name>]
+    aoc new --day <day>
+    aoc watch --day <day> [--dataset <name>] [--answers <path>]
+    aoc viz --day <day> [--input <path> | --dataset <name>] [--fps <fps>]
+            [--paused] [--output <path> [--scale <factor>]]
+    aoc image --day <day> [--input <path> | --dataset <name>] [--kind <kind>]
+              [--scale <factor>] --output <path>
+    aoc bench [--day <day>] [--dataset <name>] [--iterations <count>]
+              [--memory] [--output <path>]
+              [--compare <path> [--threshold <percent>]]
+
+Options:
+    --input <path>      Read the input from a file, or from stdin if it's -.
+    --dataset <name>    Read the input from <data-dir>/day<day>/<name>.txt,
+                        real by default. For gen, write it there instead of
+                        to stdout.
+    --data-dir <path>   The directory with the datasets, $AOC_DATA or data by
+                        default.
+    --format <format>   Print the results of run and verify as text or as
+                        json, text by default.
+    -v, -q              Log more or less, up to -vvv for everything or -qq
+                        for nothing.
+    --log <levels>      The log level of single days, like day9=trace, or of
+                        everything with only a level, on top of $AOC_LOG.
+    --answers <path>    The file with the known answers, answers.toml by
+                        default.
+    --answer <answer>   Submit this answer instead of solving the input.
+    --history <path>    The file with earlier submissions, submissions.jsonl
+                        by default.
+    --seed <seed>       The seed of the generated input, 0 by default.
+    --size <size>       The size of the generated input, in elves, moves,
+                        lines and so on. The size of real inputs by default.
+    --jobs <count>      How many days run --all runs at once, as many as
+                        there are processors by default.
+    --stream            Solve the day a line at a time instead of reading the
+                        whole input first, for days that can.
+    --iterations <count>
+                        How often every phase is timed, 10 by default.
+    --fps <fps>         The frames per second of viz, 10 by default.
+    --paused            Start viz paused, to go through it a step at a time.
+    --output <path>     Save the timings as JSON. For viz, save every frame
+                        as text instead of playing them, or as an animation
+                        if the path ends with .png. For image, the picture,
+                        as .png or .ppm.
+    --kind <kind>       Only save this picture of the day, like heights or
+                        scenic for day 8. Every picture by default, with its
+                        kind added to the name of the file.
+    --scale <factor>    How many pixels wide every cell of a picture is, 4 by
+                        default.
+    --memory            Also measure the most memory every phase needs, which
+                        makes every allocation a little slower.
+    --compare <path>    Compare the timings to ones saved with --output.
+    --threshold <percent>
+                        How much slower a phase may get before it is a
+                        regression, 10 by default.";
+
+/// How the results are printed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// For people to read.
+    #[default]
+    Text,
+    /// Only the results, as a single JSON document.
+    Json,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "{value} isn't a valid format, expected text or json."
+            )),
+        }
+    }
+}
+
+/// Which days to run.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Days {
+    All,
+    Single(u8),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        days: Days,
+        /// Only run this part, or both parts if there is none.
+        part: Option<Part>,
+        input: Input,
+        /// How many days to run at once with --all, or as many as there are
+        /// processors if there is none.
+        jobs: Option<usize>,
+        /// Solve a single day a line at a time.
+        stream: bool,
+    },
+    /// Check the solvers against the known answers.
+    Verify {
+        /// Only verify this day, or every day if there is none.
+        day: Option<u8>,
+        /// Only verify this dataset, or every dataset if there is none.
+        dataset: Option<String>,
+        answers: Option<PathBuf>,
+    },
+    /// Download the personal inputs that aren't in the data directory yet.
+    Fetch { days: Days },
+    /// Solve a part and send the answer to the website.
+    Submit {
+        day: u8,
+        part: Part,
+        input: Input,
+        /// The answer to send instead of solving the input.
+        answer: Option<String>,
+        history: Option<PathBuf>,
+    },
+    /// Make a random input.
+    Generate {
+        day: u8,
+        seed: u64,
+        /// The size of real inputs if there is none.
+        size: Option<usize>,
+        /// The dataset to save the input as, or stdout if there is none.
+        dataset: Option<String>,
+    },
+    /// Add the module of a new day, with everything it needs to be run.
+    New { day: u8 },
+    /// Rerun the tests and a dataset of a day whenever it changes.
+    Watch {
+        day: u8,
+        dataset: String,
+        answers: Option<PathBuf>,
+    },
+    /// Play the simulation of a day in the terminal.
+    Visualize {
+        day: u8,
+        input: Input,
+        fps: u32,
+        paused: bool,
+        /// The file to save the frames to instead of playing them.
+        output: Option<PathBuf>,
+        scale: usize,
+    },
+    /// Save pictures of a day.
+    Image {
+        day: u8,
+        input: Input,
+        /// Only save this picture, or all of them if there is none.
+        kind: Option<String>,
+        scale: usize,
+        output: PathBuf,
+    },
+    /// Time the parser and both parts of every day.
+    Bench {
+        /// Only time this day, or every day if there is none.
+        day: Option<u8>,
+        dataset: String,
+        iterations: u32,
+        /// Whether to count allocations, to measure memory as well.
+        memory: bool,
+        /// Where to save the timings.
+        output: Option<PathBuf>,
+        /// The saved timings to compare to.
+        compare: Option<PathBuf>,
+        /// How much slower than the saved timings a phase may get, in
+        /// percent.
+        threshold: u32,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProgramArguments {
+    /// The `--data-dir` given for any command.
+    pub data_directory: Option<PathBuf>,
+    /// The `--format` given for any command.
+    pub format: Format,
+    /// What to log, from `-v`, `-q` and `--log`.
+    pub log: Filter,
+    pub command: Command,
+}
+
+impl ProgramArguments {
+    /// Get the arguments automatically.
+    pub fn from_env() -> Result<ProgramArguments, String> {
+        // Skip the program name.
+        Self::parse_with_log_variable(env::args().skip(1), env::var(LOG_VARIABLE).ok().as_deref())
+    }
+
+    /// Parse the arguments, without the program name.
+    pub fn parse(arguments: impl IntoIterator<Item = String>) -> Result<ProgramArguments, String> {
+        Self::parse_with_log_variable(arguments, None)
+    }
+
+    /// Parse the arguments like [`parse`](Self::parse), logging what the
+    /// directives of `$AOC_LOG` say unless the flags say otherwise.
+    pub fn parse_with_log_variable(
+        arguments: impl IntoIterator<Item = String>,
+        log_variable: Option<&str>,
+    ) -> Result<ProgramArguments, String> {
+        // Global options can be anywhere, so take them out before parsing the
+        // command.
+        let mut data_directory = None;
+        let mut format = Format::default();
+        let mut verbosity = 0i8;
+        let mut directives = Vec::new();
+        let mut command_arguments = Vec::new();
+        let mut arguments = arguments.into_iter();
+        while let Some(argument) = arguments.next() {
+            if argument == "--data-dir" {
+                data_directory = Some(PathBuf::from(Self::value(&mut arguments, "--data-dir")?));
+            } else if argument == "--format" {
+                format = Format::try_from(Self::value(&mut arguments, "--format")?.as_str())?;
+            } else if argument == "--log" {
+                directives.push(Self::value(&mut arguments, "--log")?);
+            } else if let Some(flags) = argument
+                .strip_prefix('-')
+                .filter(|flags| !flags.is_empty() && flags.chars().all(|flag| "vq".contains(flag)))
+            {
+                for flag in flags.chars() {
+                    verbosity += if flag == 'v' { 1 } else { -1 };
+                }
+            } else {
+                command_arguments.push(argument);
+            }
+        }
+        let mut arguments = command_arguments.into_iter();
+
+        let mut log = Filter::default();
+        if let Some(variable) = log_variable {
+            log.add_directives(variable)
+                .map_err(|error| format!("Invalid ${LOG_VARIABLE}: {error}"))?;
+        }
+        if verbosity != 0 {
+            log.default = Filter::verbosity(verbosity).default;
+        }
+        for directives in directives {
+            log.add_directives(&directives)?;
+        }
+
+        let command = match arguments.next().as_deref() {
+            Some("run") => Self::parse_run(arguments)?,
+            Some("verify") => Self::parse_verify(arguments)?,
+            Some("fetch") => Self::parse_fetch(arguments)?,
+            Some("submit") => Self::parse_submit(arguments)?,
+            Some("gen") => Self::parse_generate(arguments)?,
+            Some("new") => Self::parse_new(arguments)?,
+            Some("watch") => Self::parse_watch(arguments)?,
+            Some("viz") => Self::parse_visualize(arguments)?,
+            Some("image") => Self::parse_image(arguments)?,
+            Some("bench") => Self::parse_bench(arguments)?,
+            Some(other) => return Err(format!("Unknown command {other}.")),
+            None => return Err("No command given.".to_string()),
+        };
+
+        Ok(Self {
+            data_directory,
+            format,
+            log,
+            command,
+        })
+    }
+
+    fn parse_run(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut all = false;
+        let mut part = None;
+        let mut input = None;
+        let mut dataset = None;
+        let mut jobs = None;
+        let mut stream = false;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--all" => all = true,
+                "--stream" => stream = true,
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--part" => {
+                    part = Some(Part::try_from(
+                        Self::value(&mut arguments, "--part")?.as_str(),
+                    )?)
+                }
+                "--input" => {
+                    input = Some(Input::from_argument(&Self::value(
+                        &mut arguments,
+                        "--input",
+                    )?))
+                }
+                "--dataset" => {
+                    dataset = Some(Input::Dataset(Self::value(&mut arguments, "--dataset")?))
+                }
+                "--jobs" => {
+                    let value = Self::value(&mut arguments, "--jobs")?;
+                    jobs = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|jobs| *jobs > 0)
+                            .ok_or(format!("{value} isn't a valid number of jobs."))?,
+                    );
+                }
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        if jobs.is_some() && !all {
+            return Err("--jobs can only be used with --all.".to_string());
+        }
+        if stream && all {
+            return Err("--stream can only be used with a single day.".to_string());
+        }
+        let days = match (day, all) {
+            (Some(day), false) => Days::Single(day),
+            (None, true) if input.is_none() => Days::All,
+            (None, true) => return Err("--input can only be used with a single day.".to_string()),
+            (Some(_), true) => return Err("--day and --all can't be used together.".to_string()),
+            (None, false) => return Err("Either --day or --all is required.".to_string()),
+        };
+        let input = match (input, dataset) {
+            (Some(_), Some(_)) => {
+                return Err("--input and --dataset can't be used together.".to_string())
+            }
+            (input, dataset) => input.or(dataset).unwrap_or_default(),
+        };
+
+        Ok(Command::Run {
+            days,
+            part,
+            input,
+            jobs,
+            stream,
+        })
+    }
+
+    fn parse_verify(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut dataset = None;
+        let mut answers = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--dataset" => dataset = Some(Self::value(&mut arguments, "--dataset")?),
+                "--answers" => {
+                    answers = Some(PathBuf::from(Self::value(&mut arguments, "--answers")?))
+                }
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        Ok(Command::Verify {
+            day,
+            dataset,
+            answers,
+        })
+    }
+
+    fn parse_fetch(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut all = false;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--all" => all = true,
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        let days = match (day, all) {
+            (Some(day), false) => Days::Single(day),
+            (None, true) => Days::All,
+            (Some(_), true) => return Err("--day and --all can't be used together.".to_string()),
+            (None, false) => return Err("Either --day or --all is required.".to_string()),
+        };
+        Ok(Command::Fetch { days })
+    }
+
+    fn parse_submit(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut part = None;
+        let mut input = None;
+        let mut dataset = None;
+        let mut answer = None;
+        let mut history = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--part" => {
+                    part = Some(Part::try_from(
+                        Self::value(&mut arguments, "--part")?.as_str(),
+                    )?)
+                }
+                "--input" => {
+                    input = Some(Input::from_argument(&Self::value(
+                        &mut arguments,
+                        "--input",
+                    )?))
+                }
+                "--dataset" => {
+                    dataset = Some(Input::Dataset(Self::value(&mut arguments, "--dataset")?))
+                }
+                "--answer" => answer = Some(Self::value(&mut arguments, "--answer")?),
+                "--history" => {
+                    history = Some(PathBuf::from(Self::value(&mut arguments, "--history")?))
+                }
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        let input = match (input, dataset) {
+            (Some(_), Some(_)) => {
+                return Err("--input and --dataset can't be used together.".to_string())
+            }
+            (input, dataset) => input.or(dataset).unwrap_or_default(),
+        };
+        Ok(Command::Submit {
+            day: day.ok_or("--day is required.")?,
+            part: part.ok_or("--part is required.")?,
+            input,
+            answer,
+            history,
+        })
+    }
+
+    fn parse_generate(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut seed = 0;
+        let mut size = None;
+        let mut dataset = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--seed" => {
+                    let value = Self::value(&mut arguments, "--seed")?;
+                    seed = value
+                        .parse()
+                        .map_err(|_| format!("{value} isn't a valid seed."))?;
+                }
+                "--size" => {
+                    let value = Self::value(&mut arguments, "--size")?;
+                    size = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("{value} isn't a valid size."))?,
+                    );
+                }
+                "--dataset" => dataset = Some(Self::value(&mut arguments, "--dataset")?),
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        Ok(Command::Generate {
+            day: day.ok_or("--day is required.")?,
+            seed,
+            size,
+            dataset,
+        })
+    }
+
+    fn parse_new(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        Ok(Command::New {
+            day: day.ok_or("--day is required.")?,
+        })
+    }
+
+    fn parse_watch(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut dataset = DEFAULT_DATASET.to_string();
+        let mut answers = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--dataset" => dataset = Self::value(&mut arguments, "--dataset")?,
+                "--answers" => {
+                    answers = Some(PathBuf::from(Self::value(&mut arguments, "--answers")?))
+                }
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        Ok(Command::Watch {
+            day: day.ok_or("--day is required.")?,
+            dataset,
+            answers,
+        })
+    }
+
+    fn parse_visualize(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut input = None;
+        let mut dataset = None;
+        let mut fps = DEFAULT_FPS;
+        let mut paused = false;
+        let mut output = None;
+        let mut scale = DEFAULT_SCALE;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--input" => {
+                    input = Some(Input::from_argument(&Self::value(
+                        &mut arguments,
+                        "--input",
+                    )?))
+                }
+                "--dataset" => {
+                    dataset = Some(Input::Dataset(Self::value(&mut arguments, "--dataset")?))
+                }
+                "--fps" => {
+                    let value = Self::value(&mut arguments, "--fps")?;
+                    fps = value
+                        .parse()
+                        .ok()
+                        .filter(|fps| (1..=1000).contains(fps))
+                        .ok_or(format!(
+                            "{value} isn't a valid number of frames per second."
+                        ))?;
+                }
+                "--paused" => paused = true,
+                "--output" => {
+                    output = Some(PathBuf::from(Self::value(&mut arguments, "--output")?))
+                }
+                "--scale" => scale = Self::scale(&mut arguments)?,
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        let input = match (input, dataset) {
+            (Some(_), Some(_)) => {
+                return Err("--input and --dataset can't be used together.".to_string())
+            }
+            (input, dataset) => input.or(dataset).unwrap_or_default(),
+        };
+        Ok(Command::Visualize {
+            day: day.ok_or("--day is required.")?,
+            input,
+            fps,
+            paused,
+            output,
+            scale,
+        })
+    }
+
+    fn parse_image(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut input = None;
+        let mut dataset = None;
+        let mut kind = None;
+        let mut scale = DEFAULT_SCALE;
+        let mut output = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--input" => {
+                    input = Some(Input::from_argument(&Self::value(
+                        &mut arguments,
+                        "--input",
+                    )?))
+                }
+                "--dataset" => {
+                    dataset = Some(Input::Dataset(Self::value(&mut arguments, "--dataset")?))
+                }
+                "--kind" => kind = Some(Self::value(&mut arguments, "--kind")?),
+                "--scale" => scale = Self::scale(&mut arguments)?,
+                "--output" => {
+                    output = Some(PathBuf::from(Self::value(&mut arguments, "--output")?))
+                }
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        let input = match (input, dataset) {
+            (Some(_), Some(_)) => {
+                return Err("--input and --dataset can't be used together.".to_string())
+            }
+            (input, dataset) => input.or(dataset).unwrap_or_default(),
+        };
+        Ok(Command::Image {
+            day: day.ok_or("--day is required.")?,
+            input,
+            kind,
+            scale,
+            output: output.ok_or("--output is required.")?,
+        })
+    }
+
+    fn parse_bench(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+        let mut day = None;
+        let mut dataset = DEFAULT_DATASET.to_string();
+        let mut iterations = DEFAULT_ITERATIONS;
+        let mut memory = false;
+        let mut output = None;
+        let mut compare = None;
+        let mut threshold = None;
+
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--day" => day = Some(Self::day(&mut arguments)?),
+                "--dataset" => dataset = Self::value(&mut arguments, "--dataset")?,
+                "--iterations" => {
+                    let value = Self::value(&mut arguments, "--iterations")?;
+                    iterations = value
+                        .parse()
+                        .ok()
+                        .filter(|iterations| *iterations > 0)
+                        .ok_or(format!("{value} isn't a valid number of iterations."))?;
+                }
+                "--memory" => memory = true,
+                "--output" => {
+                    output = Some(PathBuf::from(Self::value(&mut arguments, "--output")?))
+                }
+                "--compare" => {
+                    compare = Some(PathBuf::from(Self::value(&mut arguments, "--compare")?))
+                }
+                "--threshold" => {
+                    let value = Self::value(&mut arguments, "--threshold")?;
+                    threshold = Some(
+                        value
+                            .trim_end_matches('%')
+                            .parse()
+                            .map_err(|_| format!("{value} isn't a valid percentage."))?,
+                    );
+                }
+                other => return Err(format!("Unknown argument {other}.")),
+            }
+        }
+
+        if threshold.is_some() && compare.is_none() {
+            return Err("--threshold can only be used with --compare.".to_string());
+        }
+
+        Ok(Command::Bench {
+            day,
+            dataset,
+            iterations,
+            memory,
+            output,
+            compare,
+            threshold: threshold.unwrap_or(DEFAULT_THRESHOLD),
+        })
+    }
+
+    fn day(arguments: &mut impl Iterator<Item = String>) -> Result<u8, String> {
+        let value = Self::value(arguments, "--day")?;
+        value
+            .parse::<u8>()
+            .map_err(|_| format!("{value} isn't a valid day."))
+    }
+
+    fn scale(arguments: &mut impl Iterator<Item = String>) -> Result<usize, String> {
+        let value = Self::value(arguments, "--scale")?;
+        value
+            .parse()
+            .ok()
+            .filter(|scale| (1..=64).contains(scale))
+            .ok_or(format!("{value} isn't a valid scale."))
+    }
+
+    fn value(arguments: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+        arguments
+            .next()
+            .ok_or_else(|| format!("{flag} needs a value."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::LevelFilter;
+
+    fn parse(arguments: &str) -> Result<ProgramArguments, String> {
+        ProgramArguments::parse(arguments.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn run_single_day() {
+        assert_eq!(
+            parse("run --day 7 --part 2 --input input.txt"),
+            Ok(ProgramArguments {
+                data_directory: None,
+                format: Format::Text,
+                log: Filter::default(),
+                command: Command::Run {
+                    days: Days::Single(7),
+                    part: Some(Part::Two),
+                    input: Input::Path(PathBuf::from("input.txt")),
+                    jobs: None,
+                    stream: false,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn run_all_datasets() {
+        assert_eq!(
+            parse("run --all --dataset example --data-dir inputs"),
+            Ok(ProgramArguments {
+                data_directory: Some(PathBuf::from("inputs")),
+                format: Format::Text,
+                log: Filter::default(),
+                command: Command::Run {
+                    days: Days::All,
+                    part: None,
+                    input: Input::Dataset("example".to_string()),
+                    jobs: None,
+                    stream: false,
+                }
+            })
+        );
+        assert_eq!(
+            parse("--data-dir inputs run --day 1 --input - --format json"),
+            Ok(ProgramArguments {
+                data_directory: Some(PathBuf::from("inputs")),
+                format: Format::Json,
+                log: Filter::default(),
+                command: Command::Run {
+                    days: Days::Single(1),
+                    part: None,
+                    input: Input::Stdin,
+                    jobs: None,
+                    stream: false,
+                }
+            })
+        );
+        assert!(matches!(
+            parse("run --all --jobs 3").map(|arguments| arguments.command),
+            Ok(Command::Run { jobs: Some(3), .. })
+        ));
+        assert!(matches!(
+            parse("run --day 9 --stream").map(|arguments| arguments.command),
+            Ok(Command::Run { stream: true, .. })
+        ));
+    }
+
+    #[test]
+    fn run_invalid() {
+        assert!(parse("run").is_err());
+        assert!(parse("run --all --day 3").is_err());
+        assert!(parse("run --all --input input.txt").is_err());
+        assert!(parse("run --day 3 --part 3").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("run --day 3 --input - --dataset example").is_err());
+        assert!(parse("run --day 3 --format yaml").is_err());
+        assert!(parse("run --all --jobs 0").is_err());
+        assert!(parse("run --day 3 --jobs 2").is_err());
+        assert!(parse("run --all --stream").is_err());
+    }
+
+    #[test]
+    fn log_levels() {
+        let arguments = parse("-vv run --day 9 -q --log day9=trace").unwrap();
+        let mut log = Filter::verbosity(1);
+        log.add_directives("day9=trace").unwrap();
+        assert_eq!(arguments.log, log);
+        assert!(parse("run --day 9 --log day9=loud").is_err());
+    }
+
+    #[test]
+    fn flags_override_log_variable() {
+        let parse = |arguments: &str, variable| {
+            ProgramArguments::parse_with_log_variable(
+                arguments.split_whitespace().map(String::from),
+                Some(variable),
+            )
+            .map(|arguments| arguments.log)
+        };
+        let mut log = Filter::verbosity(3);
+        log.add_directives("day9=debug").unwrap();
+        assert_eq!(parse("run --all", "day9=debug,trace"), Ok(log.clone()));
+        log.default = LevelFilter::Error;
+        assert_eq!(parse("-q run --all", "day9=debug,trace"), Ok(log.clone()));
+        log.default = LevelFilter::Warn;
+        assert_eq!(parse("run --all --log warn", "day9=debug,trace"), Ok(log));
+        assert!(parse("run --all", "loud").is_err());
+    }
+
+    #[test]
+    fn bench_compare() {
+        assert_eq!(
+            parse("bench --compare baseline.json --threshold 25% --iterations 3 --memory"),
+            Ok(ProgramArguments {
+                data_directory: None,
+                format: Format::Text,
+                log: Filter::default(),
+                command: Command::Bench {
+                    day: None,
+                    dataset: DEFAULT_DATASET.to_string(),
+                    iterations: 3,
+                    memory: true,
+                    output: None,
+                    compare: Some(PathBuf::from("baseline.json")),
+                    threshold: 25,
+                }
+            })
+        );
+        assert!(parse("bench --threshold 5").is_err());
+        assert!(parse("bench --iterations 0").is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! Timing the parser and both parts of every day separately, and comparing
+//! those timings to an earlier run.
+//!
+//! Days that can be solved a line at a time are also timed reading the input
+//! like that. When allocations are counted, every phase also records the most
+//! memory it needed at once, to see what not keeping the whole input around
+//! saves.
+
+use std::{
+    fmt::Display,
+    fs,
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    memory::{self, format_bytes},
+    ParseError, Solution,
+};
+
+/// How often every phase is run when no number is given.
+pub const DEFAULT_ITERATIONS: u32 = 10;
+
+/// How much slower than the baseline a phase may get, in percent, before it
+/// counts as a regression.
+pub const DEFAULT_THRESHOLD: u32 = 10;
+
+/// A part of solving a day that is timed on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+    /// Reading the input a line at a time, for days that can be solved like
+    /// that.
+    Stream,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [Phase::Parse, Phase::Part1, Phase::Part2, Phase::Stream];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+            Phase::Stream => "stream",
+        }
+    }
+}
+
+impl TryFrom<&str> for Phase {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Phase::ALL
+            .into_iter()
+            .find(|phase| phase.name() == value)
+            .ok_or_else(|| {
+                format!("{value} isn't a phase, expected parse, part1, part2 or stream.")
+            })
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// How long a phase took over a number of runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub iterations: u32,
+    pub min: Duration,
+    pub median: Duration,
+    /// The most bytes allocated at once during any of the runs, see
+    /// [`memory::peak_during`], if allocations were counted.
+    pub peak_memory: Option<usize>,
+}
+
+impl Timing {
+    fn from_samples(mut samples: Vec<Duration>, peak_memory: Option<usize>) -> Self {
+        samples.sort();
+        Self {
+            iterations: samples.len() as u32,
+            min: samples.first().copied().unwrap_or_default(),
+            median: samples.get(samples.len() / 2).copied().unwrap_or_default(),
+            peak_memory,
+        }
+    }
+}
+
+/// The timings of the parser and both parts, in that order, and of reading
+/// the input a line at a time for days that can be solved like that.
+pub type PhaseTimings = Vec<(Phase, Timing)>;
+
+/// Run `f` the given number of times, but at least once.
+pub fn time<T>(iterations: u32, mut f: impl FnMut() -> T) -> Timing {
+    let mut peak_memory = None;
+    let samples = (0..iterations.max(1))
+        .map(|_| {
+            let ((output, elapsed), peak) = memory::peak_during(|| {
+                let start = Instant::now();
+                let output = f();
+                (output, start.elapsed())
+            });
+            peak_memory = peak_memory.max(peak);
+            // Dropping the output isn't part of the phase.
+            drop(black_box(output));
+            elapsed
+        })
+        .collect();
+    Timing::from_samples(samples, peak_memory)
+}
+
+/// Time the parser and both parts of a solution on the same input.
+///
+/// The parts are timed even if they fail, since failing can be slow too.
+pub fn measure<S: Solution>(input: &str, iterations: u32) -> Result<PhaseTimings, ParseError> {
+    let parsed = S::parse(input)?;
+    Ok(vec![
+        (
+            Phase::Parse,
+            time(iterations, || S::parse(black_box(input))),
+        ),
+        (
+            Phase::Part1,
+            time(iterations, || S::part1(black_box(&parsed))),
+        ),
+        (
+            Phase::Part2,
+            time(iterations, || S::part2(black_box(&parsed))),
+        ),
+    ])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    pub day: u8,
+    pub dataset: String,
+    pub phase: Phase,
+    pub timing: Timing,
+}
+
+/// The timings of a benchmark run, which can be saved as JSON to compare
+/// later runs against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchReport(pub Vec<Measurement>);
+
+impl BenchReport {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+        Self::try_from(content.as_str())
+            .map_err(|error| format!("Invalid benchmark report in {}: {error}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, format!("{:#}\n", self.to_json()))
+            .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+    }
+
+    pub fn to_json(&self) -> Value {
+        let measurements = self
+            .0
+            .iter()
+            .map(|measurement| {
+                json!({
+                    "day": measurement.day,
+                    "dataset": measurement.dataset,
+                    "phase": measurement.phase.name(),
+                    "iterations": measurement.timing.iterations,
+                    "min_ns": nanoseconds(measurement.timing.min),
+                    "median_ns": nanoseconds(measurement.timing.median),
+                    "peak_bytes": measurement
+                        .timing
+                        .peak_memory
+                        .map(|bytes| i64::try_from(bytes).unwrap_or(i64::MAX)),
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "measurements": measurements })
+    }
+
+    /// The measurement of the same day, dataset and phase.
+    pub fn find(&self, day: u8, dataset: &str, phase: Phase) -> Option<&Measurement> {
+        self.0.iter().find(|measurement| {
+            measurement.day == day && measurement.dataset == dataset && measurement.phase == phase
+        })
+    }
+}
+
+fn nanoseconds(duration: Duration) -> i64 {
+    duration.as_nanos().try_into().unwrap_or(i64::MAX)
+}
+
+impl TryFrom<&str> for BenchReport {
+    type Error = String;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let json = serde_json::from_str::<Value>(input).map_err(|error| error.to_string())?;
+        let measurements = json
+            .get("measurements")
+            .and_then(Value::as_array)
+            .ok_or("expected a measurements array")?;
+
+        measurements
+            .iter()
+            .enumerate()
+            .map(|(index, measurement)| {
+                let field = |name: &str| {
+                    measurement
+                        .get(name)
+                        .ok_or_else(|| format!("measurement {index} has no {name}"))
+                };
+                let number = |name: &str| {
+                    field(name)?
+                        .as_i64()
+                        .ok_or_else(|| format!("{name} of measurement {index} isn't a number"))
+                };
+                let text = |name: &str| {
+                    field(name)?
+                        .as_str()
+                        .ok_or_else(|| format!("{name} of measurement {index} isn't a string"))
+                };
+                let duration = |name: &str| {
+                    number(name)?
+                        .try_into()
+                        .map(Duration::from_nanos)
+                        .map_err(|_| format!("{name} of measurement {index} is negative"))
+                };
+
+                Ok(Measurement {
+                    day: number("day")?
+                        .try_into()
+                        .map_err(|_| format!("day of measurement {index} isn't a day"))?,
+                    dataset: text("dataset")?.to_string(),
+                    phase: Phase::try_from(text("phase")?)?,
+                    timing: Timing {
+                        iterations: number("iterations")?.try_into().map_err(|_| {
+                            format!("iterations of measurement {index} is out of range")
+                        })?,
+                        min: duration("min_ns")?,
+                        median: duration("median_ns")?,
+                        // Runs that didn't count allocations have none, and
+                        // reports from before memory was measured don't
+                        // have it at all.
+                        peak_memory: match measurement.get("peak_bytes") {
+                            None | Some(Value::Null) => None,
+                            Some(_) => Some(number("peak_bytes")?.try_into().map_err(|_| {
+                                format!("peak_bytes of measurement {index} is negative")
+                            })?),
+                        },
+                    },
+                })
+            })
+            .collect::<Result<_, String>>()
+            .map(BenchReport)
+    }
+}
+
+/// The median of a phase in the baseline and in the current run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub day: u8,
+    pub dataset: String,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Comparison {
+    /// How much slower the current run is, in percent. Negative if it got
+    /// faster.
+    pub fn change(&self) -> f64 {
+        if self.baseline.is_zero() {
+            return 0.0;
+        }
+        (self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0) * 100.0
+    }
+
+    pub fn is_regression(&self, threshold: u32) -> bool {
+        self.current.as_nanos() * 100 > self.baseline.as_nanos() * (100 + u128::from(threshold))
+    }
+}
+
+/// Compare every measurement of the current run that is also in the baseline.
+pub fn compare(baseline: &BenchReport, current: &BenchReport) -> Vec<Comparison> {
+    current
+        .0
+        .iter()
+        .filter_map(|measurement| {
+            let previous =
+                baseline.find(measurement.day, &measurement.dataset, measurement.phase)?;
+            Some(Comparison {
+                day: measurement.day,
+                dataset: measurement.dataset.clone(),
+                phase: measurement.phase,
+                baseline: previous.timing.median,
+                current: measurement.timing.median,
+            })
+        })
+        .collect()
+}
+
+fn dataset_width<'a>(datasets: impl Iterator<Item = &'a str>) -> usize {
+    datasets
+        .map(str::len)
+        .chain(["Dataset".len()])
+        .max()
+        .unwrap_or_default()
+}
+
+/// The measurements as a table.
+pub struct BenchTable<'a>(pub &'a [Measurement]);
+
+impl Display for BenchTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dataset_width = dataset_width(
+            self.0
+                .iter()
+                .map(|measurement| measurement.dataset.as_str()),
+        );
+        write!(
+            f,
+            "Day  {:dataset_width$}  Phase   {:>12}  {:>12}  {:>11}  Runs",
+            "Dataset", "Median", "Min", "Peak memory"
+        )?;
+        for measurement in self.0 {
+            write!(
+                f,
+                "\n{:>3}  {:dataset_width$}  {:6}  {:>12}  {:>12}  {:>11}  {:>4}",
+                measurement.day,
+                measurement.dataset,
+                measurement.phase,
+                format!("{:.2?}", measurement.timing.median),
+                format!("{:.2?}", measurement.timing.min),
+                measurement
+                    .timing
+                    .peak_memory
+                    .map_or("-".to_string(), format_bytes),
+                measurement.timing.iterations
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The comparisons as a table, marking the regressions.
+pub struct ComparisonTable<'a> {
+    pub comparisons: &'a [Comparison],
+    /// The threshold in percent.
+    pub threshold: u32,
+}
+
+impl Display for ComparisonTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dataset_width = dataset_width(
+            self.comparisons
+                .iter()
+                .map(|comparison| comparison.dataset.as_str()),
+        );
+        writeln!(
+            f,
+            "Day  {:dataset_width$}  Phase   {:>12}  {:>12}  {:>8}",
+            "Dataset", "Baseline", "Current", "Change"
+        )?;
+        for comparison in self.comparisons {
+            write!(
+                f,
+                "{:>3}  {:dataset_width$}  {:6}  {:>12}  {:>12}  {:>+7.1}%",
+                comparison.day,
+                comparison.dataset,
+                comparison.phase,
+                format!("{:.2?}", comparison.baseline),
+                format!("{:.2?}", comparison.current),
+                comparison.change()
+            )?;
+            if comparison.is_regression(self.threshold) {
+                write!(f, "  REGRESSION")?;
+            }
+            writeln!(f)?;
+        }
+        let regressions = self
+            .comparisons
+            .iter()
+            .filter(|comparison| comparison.is_regression(self.threshold))
+            .count();
+        write!(
+            f,
+            "{regressions} of {} phases more than {}% slower",
+            self.comparisons.len(),
+            self.threshold
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1::Day1;
+
+    fn measurement(phase: Phase, median: u64) -> Measurement {
+        Measurement {
+            day: 8,
+            dataset: "real".to_string(),
+            phase,
+            timing: Timing {
+                iterations: 10,
+                min: Duration::from_nanos(median / 2),
+                median: Duration::from_nanos(median),
+                peak_memory: Some(4096),
+            },
+        }
+    }
+
+    #[test]
+    fn measure_every_phase() {
+        let input = include_str!("../data/day1/example.txt");
+        let timings = measure::<Day1>(input, 3).unwrap();
+        assert_eq!(
+            timings.iter().map(|(phase, _)| *phase).collect::<Vec<_>>(),
+            Phase::ALL[..3]
+        );
+        assert!(timings.iter().all(|(_, timing)| timing.iterations == 3));
+        assert!(measure::<Day1>("1\nx", 3).is_err());
+    }
+
+    #[test]
+    fn report_round_trip() {
+        let mut report = BenchReport(vec![
+            measurement(Phase::Parse, 1_500),
+            measurement(Phase::Part2, 2_000_000),
+        ]);
+        // Without counting allocations there is no peak.
+        report.0[1].timing.peak_memory = None;
+        let json = format!("{:#}", report.to_json());
+        assert_eq!(BenchReport::try_from(json.as_str()), Ok(report));
+        assert!(BenchReport::try_from("{\"measurements\": [{\"day\": 1}]}").is_err());
+
+        // Reports from before memory was measured can still be compared to.
+        let older = BenchReport::try_from(
+            "{\"measurements\": [{\"day\": 8, \"dataset\": \"real\", \"phase\": \"parse\", \
+             \"iterations\": 10, \"min_ns\": 750, \"median_ns\": 1500}]}",
+        )
+        .unwrap();
+        assert_eq!(older.0[0].timing.peak_memory, None);
+    }
+
+    #[test]
+    fn compare_regressions() {
+        let baseline = BenchReport(vec![
+            measurement(Phase::Parse, 1_000),
+            measurement(Phase::Part1, 1_000),
+            measurement(Phase::Part2, 1_000),
+        ]);
+        let current = BenchReport(vec![
+            measurement(Phase::Parse, 1_100),
+            measurement(Phase::Part1, 1_101),
+            measurement(Phase::Part2, 500),
+        ]);
+
+        let comparisons = compare(&baseline, &current);
+        let regressions = comparisons
+            .iter()
+            .map(|comparison| comparison.is_regression(10))
+            .collect::<Vec<_>>();
+        assert_eq!(regressions, [false, true, false]);
+        assert_eq!(comparisons[2].change(), -50.0);
+    }
+}
@@ -0,0 +1,454 @@
+use std::{io, path::Path, process::ExitCode, sync::mpsc, thread, time::Instant};
+
+use aoc2022::{
+    answers::{Answers, DEFAULT_ANSWERS_FILE},
+    arguments::{Command, Days, Format, USAGE},
+    bench::{self, BenchReport, BenchTable, ComparisonTable, Measurement},
+    client::{self, FetchError, LazyClient},
+    generate::Rng,
+    input::{self, Input},
+    logging,
+    memory::{self, CountingAllocator},
+    parallel::{self, DayOutcome, DayRun, RunTable},
+    scaffold,
+    solution::{self, Part, RegisteredSolution, SOLUTIONS},
+    submit::{self, History, SubmitError, Verdict, DEFAULT_HISTORY_FILE},
+    verify::{self, Outcome, VerificationTable},
+    visualize::{self, Player},
+    watch::Watch,
+    Answer, ProgramArguments, ProgramError,
+};
+use serde_json::Value;
+
+// Counts the memory every phase of the benchmarks needs, once bench --memory
+// turns counting on.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Time a single solution on a dataset, adding the timings to the report.
+///
+/// Returns whether the input could be read and parsed.
+fn bench(
+    solution: &RegisteredSolution,
+    dataset: &str,
+    iterations: u32,
+    data_directory: &Path,
+    report: &mut BenchReport,
+) -> bool {
+    let input = match Input::Dataset(dataset.to_string()).read(solution.day, data_directory) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("{error}");
+            return false;
+        }
+    };
+
+    match solution.bench(&input, iterations) {
+        Ok(timings) => {
+            report
+                .0
+                .extend(timings.into_iter().map(|(phase, timing)| Measurement {
+                    day: solution.day,
+                    dataset: dataset.to_string(),
+                    phase,
+                    timing,
+                }));
+            true
+        }
+        Err(error) => {
+            eprintln!("Day {}: parse error: {error}", solution.day);
+            false
+        }
+    }
+}
+
+fn main() -> Result<ExitCode, ProgramError> {
+    let arguments = match ProgramArguments::from_env() {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    logging::init(arguments.log.clone())?;
+    let data_directory = input::data_directory(arguments.data_directory.as_deref());
+
+    let success = match arguments.command {
+        Command::Run {
+            days,
+            part,
+            input,
+            jobs,
+            stream,
+        } => {
+            let parts = match part {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            match days {
+                Days::All => {
+                    let jobs = jobs.unwrap_or_else(parallel::default_jobs);
+                    let start = Instant::now();
+                    let runs = parallel::run_all(
+                        &SOLUTIONS.iter().collect::<Vec<_>>(),
+                        &input,
+                        &data_directory,
+                        &parts,
+                        jobs,
+                    );
+                    let wall_time = start.elapsed();
+                    match arguments.format {
+                        Format::Text => println!(
+                            "{}",
+                            RunTable {
+                                runs: &runs,
+                                parts: &parts,
+                                wall_time,
+                                jobs,
+                            }
+                        ),
+                        Format::Json => println!(
+                            "{:#}",
+                            Value::Array(runs.iter().map(DayRun::to_json).collect())
+                        ),
+                    }
+                    runs.iter().all(DayRun::is_success)
+                }
+                Days::Single(day) => {
+                    let solution =
+                        solution::solution(day).ok_or(format!("Day {day} isn't solved."))?;
+                    if stream && solution.stream.is_none() {
+                        return Err(format!("Day {day} can't be solved a line at a time.").into());
+                    }
+                    let start = Instant::now();
+                    let start_cpu = parallel::thread_cpu_time();
+                    let outcome = if stream {
+                        match input.open(day, &data_directory) {
+                            Ok(mut reader) => match parallel::catch_panic(|| {
+                                solution
+                                    .stream(&mut reader, &parts)
+                                    .expect("The day can be solved a line at a time.")
+                            }) {
+                                Ok(Ok(report)) => DayOutcome::Report(report),
+                                Ok(Err(error)) => DayOutcome::InputError(
+                                    input.error(day, &data_directory, error).to_string(),
+                                ),
+                                Err(message) => DayOutcome::Panic(message),
+                            },
+                            Err(error) => DayOutcome::InputError(error.to_string()),
+                        }
+                    } else {
+                        match input.read(day, &data_directory) {
+                            Ok(input) => parallel::catch_panic(|| solution.run(&input, &parts))
+                                .map_or_else(DayOutcome::Panic, DayOutcome::Report),
+                            Err(error) => DayOutcome::InputError(error.to_string()),
+                        }
+                    };
+                    let run = DayRun {
+                        day,
+                        outcome,
+                        time: start.elapsed(),
+                        cpu_time: parallel::thread_cpu_time()
+                            .zip(start_cpu)
+                            .map(|(end, start)| end - start),
+                    };
+                    match (arguments.format, &run.outcome) {
+                        (Format::Text, DayOutcome::Report(report)) => print!("{report}"),
+                        (Format::Text, DayOutcome::InputError(error)) => eprintln!("{error}"),
+                        (Format::Text, DayOutcome::Panic(message)) => {
+                            eprintln!("Day {day} panicked: {message}")
+                        }
+                        (Format::Json, _) => {
+                            println!("{:#}", Value::Array(vec![run.to_json()]))
+                        }
+                    }
+                    run.is_success()
+                }
+            }
+        }
+        Command::Verify {
+            day,
+            dataset,
+            answers,
+        } => {
+            let answers = Answers::load(
+                answers
+                    .as_deref()
+                    .unwrap_or(Path::new(DEFAULT_ANSWERS_FILE)),
+            )?;
+            let verifications = verify::verify(&answers, &data_directory, |answer_day, name| {
+                day.is_none_or(|day| day == answer_day)
+                    && dataset.as_ref().is_none_or(|dataset| dataset == name)
+            });
+            match arguments.format {
+                Format::Text => println!("{}", VerificationTable(&verifications)),
+                Format::Json => println!(
+                    "{:#}",
+                    Value::Array(
+                        verifications
+                            .iter()
+                            .map(|verification| verification.to_json())
+                            .collect()
+                    )
+                ),
+            }
+            verifications
+                .iter()
+                .all(|verification| verification.outcome == Outcome::Pass)
+        }
+        Command::Fetch { days } => {
+            let client = LazyClient::from_env();
+            let days = match days {
+                Days::All => (1..=25).collect(),
+                Days::Single(day) => vec![day],
+            };
+            let mut fetched_all = true;
+            for day in days {
+                match client::cache_input(&client, day, &data_directory) {
+                    Ok((path, true)) => println!("Day {day}: fetched into {}", path.display()),
+                    Ok((path, false)) => println!("Day {day}: already in {}", path.display()),
+                    // The days after it won't be unlocked either.
+                    Err(error @ FetchError::NotUnlocked(_)) => {
+                        eprintln!("{error}");
+                        fetched_all = false;
+                        break;
+                    }
+                    Err(error) => {
+                        eprintln!("Day {day}: {error}");
+                        fetched_all = false;
+                    }
+                }
+            }
+            fetched_all
+        }
+        Command::Submit {
+            day,
+            part,
+            input,
+            answer,
+            history,
+        } => {
+            let answer = match answer {
+                Some(answer) => answer.parse().map_or(Answer::Text(answer), Answer::Number),
+                None => {
+                    let solution =
+                        solution::solution(day).ok_or(format!("Day {day} isn't solved."))?;
+                    let input = input.read(day, &data_directory)?;
+                    let mut report = parallel::catch_panic(|| solution.run(&input, &[part]))
+                        .map_err(|message| format!("Day {day} panicked: {message}"))?;
+                    match report.parts.pop() {
+                        Some((_, Ok(answer), _)) => answer,
+                        _ => {
+                            print!("{report}");
+                            return Ok(ExitCode::FAILURE);
+                        }
+                    }
+                }
+            };
+            let client = LazyClient::from_env();
+            let mut history = History::load(
+                history
+                    .as_deref()
+                    .unwrap_or(Path::new(DEFAULT_HISTORY_FILE)),
+            )?;
+
+            println!("Day {day} part {part}: submitting {answer}");
+            match submit::submit(&client, &mut history, day, part, &answer) {
+                Ok(verdict) => {
+                    println!("{verdict}");
+                    verdict == Verdict::Right
+                }
+                Err(error @ SubmitError::Refused(_)) => {
+                    println!("{error}");
+                    false
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Command::Generate {
+            day,
+            seed,
+            size,
+            dataset,
+        } => {
+            let solution = solution::solution(day).ok_or(format!("Day {day} isn't solved."))?;
+            let generator = &solution.generator;
+            let input =
+                (generator.generate)(&mut Rng::new(seed), size.unwrap_or(generator.real_size));
+            match dataset {
+                Some(dataset) => {
+                    let path = input::dataset_path(&data_directory, day, &dataset);
+                    if let Some(directory) = path.parent() {
+                        std::fs::create_dir_all(directory).map_err(|error| {
+                            format!("Failed to create {}: {error}", directory.display())
+                        })?;
+                    }
+                    std::fs::write(&path, input)
+                        .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+                    println!("Day {day}: generated {}", path.display());
+                }
+                None => print!("{input}"),
+            }
+            true
+        }
+        Command::New { day } => {
+            // The sources are changed in place, so this has to run from the
+            // root of the repository.
+            for path in scaffold::scaffold(Path::new("."), day)? {
+                println!("Day {day}: wrote {}", path.display());
+            }
+            println!("Fill in the example and its answers, then run cargo test day{day}.");
+            true
+        }
+        Command::Watch {
+            day,
+            dataset,
+            answers,
+        } => Watch {
+            day,
+            dataset,
+            data_directory,
+            answers: answers.unwrap_or_else(|| DEFAULT_ANSWERS_FILE.into()),
+        }
+        .watch(),
+        Command::Visualize {
+            day,
+            input,
+            fps,
+            paused,
+            output,
+            scale,
+        } => {
+            let solution = solution::solution(day).ok_or(format!("Day {day} isn't solved."))?;
+            let visualize = solution
+                .visualize
+                .ok_or(format!("Day {day} can't be visualized."))?;
+            let input = input.read(day, &data_directory)?;
+            let mut visualization = visualize(&input)?;
+            match output {
+                Some(path) => {
+                    let animated = path.extension().is_some_and(|extension| extension == "png");
+                    if animated && visualization.image().is_none() {
+                        return Err(format!("Day {day} can't be drawn.").into());
+                    }
+                    let frames = if animated {
+                        visualize::export_animation(visualization.as_mut(), &path, fps, scale)
+                    } else {
+                        visualize::export(visualization.as_mut(), &path)
+                    }
+                    .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+                    println!("Day {day}: wrote {frames} frames to {}", path.display());
+                }
+                None => {
+                    // Read the controls on their own thread, so the frames
+                    // keep playing while there are none.
+                    let (sender, controls) = mpsc::channel();
+                    thread::spawn(move || {
+                        for line in io::stdin().lines().map_while(Result::ok) {
+                            if sender.send(line).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    Player { fps, paused }.play(
+                        visualization.as_mut(),
+                        &controls,
+                        &mut io::stdout().lock(),
+                    )?;
+                }
+            }
+            true
+        }
+        Command::Image {
+            day,
+            input,
+            kind,
+            scale,
+            output,
+        } => {
+            let solution = solution::solution(day).ok_or(format!("Day {day} isn't solved."))?;
+            let draw = solution.draw.ok_or(format!("Day {day} can't be drawn."))?;
+            let mut images = draw(&input.read(day, &data_directory)?)?;
+            if let Some(kind) = &kind {
+                let kinds = images.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+                images.retain(|(name, _)| name == kind);
+                if images.is_empty() {
+                    return Err(format!(
+                        "Day {day} has no picture {kind}, expected one of {}.",
+                        kinds.join(", ")
+                    )
+                    .into());
+                }
+            }
+            let single = images.len() == 1;
+            for (name, image) in images {
+                // Every picture gets a file of its own.
+                let path = if single {
+                    output.clone()
+                } else {
+                    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+                    let extension = output.extension().unwrap_or_default().to_string_lossy();
+                    output.with_file_name(format!("{stem}-{name}.{extension}"))
+                };
+                image
+                    .scaled(scale)
+                    .save(&path)
+                    .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+                println!("Day {day}: drew {name} in {}", path.display());
+            }
+            true
+        }
+        Command::Bench {
+            day,
+            dataset,
+            iterations,
+            memory,
+            output,
+            compare,
+            threshold,
+        } => {
+            if memory {
+                memory::start_counting();
+            }
+            // Read the baseline first, so a missing one doesn't waste a run.
+            let baseline = compare.as_deref().map(BenchReport::load).transpose()?;
+            let solutions = match day {
+                Some(day) => {
+                    vec![solution::solution(day).ok_or(format!("Day {day} isn't solved."))?]
+                }
+                None => SOLUTIONS.iter().collect(),
+            };
+
+            let mut report = BenchReport::default();
+            let benched = solutions
+                .into_iter()
+                .map(|solution| bench(solution, &dataset, iterations, &data_directory, &mut report))
+                .collect::<Vec<_>>();
+            println!("{}", BenchTable(&report.0));
+            if let Some(output) = output {
+                report.save(&output)?;
+            }
+
+            let regressed = baseline.is_some_and(|baseline| {
+                let comparisons = bench::compare(&baseline, &report);
+                println!(
+                    "\n{}",
+                    ComparisonTable {
+                        comparisons: &comparisons,
+                        threshold,
+                    }
+                );
+                comparisons
+                    .iter()
+                    .any(|comparison| comparison.is_regression(threshold))
+            });
+            benched.into_iter().all(|benched| benched) && !regressed
+        }
+    };
+
+    if success {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
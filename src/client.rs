@@ -0,0 +1,398 @@
+//! Talking to the Advent of Code website, and keeping the personal puzzle
+//! inputs in the data directory so they only have to be downloaded once.
+
+use std::{
+    cell::OnceCell,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::input::{dataset_path, DEFAULT_DATASET};
+
+/// The website used when no other is given.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The environment variable that overrides the default website, for example
+/// to point at a local stand-in.
+pub const BASE_URL_VARIABLE: &str = "AOC_BASE_URL";
+
+/// The environment variable with the session token of a logged in user.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+/// The file with the session token, used when the environment variable isn't
+/// set.
+pub const SESSION_FILE: &str = ".aoc-session";
+
+pub const YEAR: u16 = 2022;
+
+/// The Unix time the first puzzle unlocked, at midnight in UTC-5.
+const FIRST_UNLOCK: u64 = 1_669_870_800;
+
+/// The reason an input couldn't be fetched.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The puzzle isn't available yet, so asking again won't help until it
+    /// is.
+    NotUnlocked(u8),
+    /// The session token is missing, or the website didn't accept it.
+    Unauthorized(String),
+    /// Any other response that wasn't the input.
+    Status { status: u16, body: String },
+    /// The website couldn't be reached at all.
+    Transport(String),
+    /// The input was fetched, but couldn't be stored.
+    Cache { path: PathBuf, error: io::Error },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::NotUnlocked(day) => write!(
+                f,
+                "Day {day} isn't unlocked yet, try again once it is on the calendar."
+            ),
+            FetchError::Unauthorized(reason) => write!(
+                f,
+                "Not logged in: {reason} Put the session cookie in ${SESSION_VARIABLE} or {SESSION_FILE}."
+            ),
+            FetchError::Status { status, body } => {
+                write!(f, "Unexpected response {status}: {}", body.trim())
+            }
+            FetchError::Transport(error) => write!(f, "Failed to reach the website: {error}"),
+            FetchError::Cache { path, error } => {
+                write!(f, "Failed to write {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Something that can get the personal puzzle input for a day.
+pub trait Fetcher {
+    fn fetch_input(&self, day: u8) -> Result<String, FetchError>;
+}
+
+/// A client for the website, or for anything that answers the same way.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("aoc2022/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// A client for the website from `$AOC_BASE_URL`, or the real one, with
+    /// the session token from `$AOC_SESSION` or `.aoc-session`.
+    pub fn from_env() -> Result<Self, FetchError> {
+        let base_url = env::var(BASE_URL_VARIABLE).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let session = match env::var(SESSION_VARIABLE) {
+            Ok(session) => session,
+            Err(_) => fs::read_to_string(SESSION_FILE)
+                .map_err(|_| FetchError::Unauthorized("no session token.".to_string()))?,
+        };
+        Ok(Self::new(&base_url, &session))
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{YEAR}{path}", self.base_url)
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, FetchError> {
+        self.response(
+            self.agent
+                .get(&self.url(path))
+                .set("Cookie", &format!("session={}", self.session))
+                .call(),
+        )
+    }
+
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, FetchError> {
+        self.response(
+            self.agent
+                .post(&self.url(path))
+                .set("Cookie", &format!("session={}", self.session))
+                .send_form(form),
+        )
+    }
+
+    fn response(
+        &self,
+        response: Result<ureq::Response, ureq::Error>,
+    ) -> Result<String, FetchError> {
+        let transport = |error: io::Error| FetchError::Transport(error.to_string());
+        match response {
+            Ok(response) => response.into_string().map_err(transport),
+            Err(ureq::Error::Status(status, response)) => Err(FetchError::Status {
+                status,
+                body: response.into_string().map_err(transport)?,
+            }),
+            Err(ureq::Error::Transport(error)) => Err(FetchError::Transport(error.to_string())),
+        }
+    }
+}
+
+impl Fetcher for Client {
+    fn fetch_input(&self, day: u8) -> Result<String, FetchError> {
+        if self.session.is_empty() {
+            return Err(FetchError::Unauthorized(
+                "the session token is empty.".to_string(),
+            ));
+        }
+        // Don't ask for puzzles that can't be there yet, the website asks
+        // not to be hammered before they unlock.
+        if SystemTime::now() < unlocks_at(day) {
+            return Err(FetchError::NotUnlocked(day));
+        }
+
+        match self.get(&format!("/day/{day}/input")) {
+            // Before a puzzle unlocks the website answers 404 with a request
+            // not to ask again until it does.
+            Err(FetchError::Status { status: 404, .. }) => Err(FetchError::NotUnlocked(day)),
+            // Without a valid session it asks to log in.
+            Err(FetchError::Status { status, body })
+                if (400..=500).contains(&status) && body.contains("log in") =>
+            {
+                Err(FetchError::Unauthorized(
+                    "the session token wasn't accepted.".to_string(),
+                ))
+            }
+            result => result,
+        }
+    }
+}
+
+/// A client that is only made the first time it is needed, so commands that
+/// find everything they need on disk work without a session token.
+pub struct LazyClient {
+    make: fn() -> Result<Client, FetchError>,
+    client: OnceCell<Client>,
+}
+
+impl LazyClient {
+    pub fn new(make: fn() -> Result<Client, FetchError>) -> Self {
+        Self {
+            make,
+            client: OnceCell::new(),
+        }
+    }
+
+    /// A client like [`Client::from_env`] makes.
+    pub fn from_env() -> Self {
+        Self::new(Client::from_env)
+    }
+
+    pub fn client(&self) -> Result<&Client, FetchError> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = (self.make)()?;
+        Ok(self.client.get_or_init(|| client))
+    }
+}
+
+impl Fetcher for LazyClient {
+    fn fetch_input(&self, day: u8) -> Result<String, FetchError> {
+        self.client()?.fetch_input(day)
+    }
+}
+
+/// The moment the puzzle of a day unlocks.
+pub fn unlocks_at(day: u8) -> SystemTime {
+    SystemTime::UNIX_EPOCH
+        + Duration::from_secs(FIRST_UNLOCK + u64::from(day.saturating_sub(1)) * 24 * 60 * 60)
+}
+
+/// Make sure the personal input of a day is in the data directory, as the
+/// `real` dataset, fetching it only if it isn't there yet.
+///
+/// Returns where the input is, and whether it had to be fetched.
+pub fn cache_input(
+    fetcher: &dyn Fetcher,
+    day: u8,
+    data_directory: &Path,
+) -> Result<(PathBuf, bool), FetchError> {
+    let path = dataset_path(data_directory, day, DEFAULT_DATASET);
+    if path.exists() {
+        return Ok((path, false));
+    }
+
+    let input = fetcher.fetch_input(day)?;
+    // Write to a temporary file first, so an interrupted write never leaves
+    // half an input that looks cached.
+    let temporary = path.with_extension("txt.part");
+    let cache_error = |error| FetchError::Cache {
+        path: path.clone(),
+        error,
+    };
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(cache_error)?;
+    }
+    fs::write(&temporary, input).map_err(cache_error)?;
+    fs::rename(&temporary, &path).map_err(cache_error)?;
+    Ok((path, true))
+}
+
+/// A stand-in for the website that answers every request with the next of
+/// the given responses, for testing clients without the network.
+#[cfg(test)]
+pub(crate) mod server {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc::{self, Receiver},
+        thread,
+    };
+
+    /// A request the server received.
+    pub struct Request {
+        /// Like `GET /2022/day/1/input`.
+        pub line: String,
+        pub headers: Vec<String>,
+        pub body: String,
+    }
+
+    /// Start the server, returning its base URL and the requests it gets.
+    pub fn start(responses: Vec<(u16, &'static str)>) -> (String, Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let Ok((stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let mut headers = Vec::new();
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    headers.push(header.trim().to_string());
+                }
+                let length = headers
+                    .iter()
+                    .find_map(|header| {
+                        let (name, value) = header.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or_default();
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Canned\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                sender
+                    .send(Request {
+                        line: line.trim().to_string(),
+                        headers,
+                        body: String::from_utf8(request_body).unwrap(),
+                    })
+                    .unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("aoc2022-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn fetch_once() {
+        let (url, requests) = server::start(vec![(200, "1000\n2000\n")]);
+        let client = Client::new(&url, "secret\n");
+        let directory = data_directory("fetch-once");
+
+        let (path, fetched) = cache_input(&client, 1, &directory).unwrap();
+        assert!(fetched);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "GET /2022/day/1/input HTTP/1.1");
+        assert!(request
+            .headers
+            .contains(&"Cookie: session=secret".to_string()));
+
+        // The server only answers once, so this has to come from the cache.
+        assert_eq!(
+            cache_input(&client, 1, &directory).unwrap(),
+            (path.clone(), false)
+        );
+
+        // Without a session only the cached days can be had.
+        let offline = LazyClient::new(|| Err(FetchError::Unauthorized("no session.".to_string())));
+        assert_eq!(cache_input(&offline, 1, &directory).unwrap(), (path, false));
+        assert!(matches!(
+            cache_input(&offline, 2, &directory),
+            Err(FetchError::Unauthorized(_))
+        ));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn fetch_errors() {
+        let (url, _requests) = server::start(vec![
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+            ),
+            (500, "Internal Server Error"),
+        ]);
+        let client = Client::new(&url, "secret");
+        let directory = data_directory("fetch-errors");
+
+        assert!(matches!(
+            cache_input(&client, 2, &directory),
+            Err(FetchError::NotUnlocked(2))
+        ));
+        assert!(!dataset_path(&directory, 2, DEFAULT_DATASET).exists());
+        assert!(matches!(
+            client.fetch_input(2),
+            Err(FetchError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            client.fetch_input(2),
+            Err(FetchError::Status { status: 500, .. })
+        ));
+        assert!(matches!(
+            Client::new(&url, "").fetch_input(2),
+            Err(FetchError::Unauthorized(_))
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use itertools::Itertools;
+
+use crate::{
+    generate::Rng,
+    parsing::{paragraphs, Line},
+    solution::{PartResult, Solution, Streaming},
+    ParseError, Part,
+};
+
+/// The calories an elf carries with the ones on the line added.
+fn add_calories(total: u32, line: &Line) -> Result<u32, ParseError> {
+    let calories = line
+        .text
+        .parse::<u32>()
+        .map_err(|_| line.error(line.text, "an amount of calories"))?;
+    total
+        .checked_add(calories)
+        .ok_or_else(|| line.error(line.text, "a smaller amount of calories"))
+}
+
+/// The total amount of calories carried by every elf, sorted from the most to
+/// the least calories.
+///
+/// Every elf's inventory is a group of lines, separated from the next elf by
+/// an empty line.
+pub fn elf_calories(input: &str) -> Result<Vec<u32>, ParseError> {
+    let calories = paragraphs(input)
+        .map(|inventory| {
+            inventory
+                .iter()
+                .try_fold(0, |total, line| add_calories(total, line))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(calories.into_iter().sorted().rev().collect())
+}
+
+/// The total amount of calories carried by the `amount` elves carrying the
+/// most calories, which can be more than a single elf can carry.
+pub fn top_calories(calories: &[u32], amount: usize) -> i64 {
+    calories.iter().take(amount).copied().map(i64::from).sum()
+}
+
+/// The elves carrying the most calories, kept while reading the inventories
+/// a line at a time.
+#[derive(Debug, Default)]
+pub struct TopElves {
+    /// The calories of the elf whose inventory is being read, if any.
+    current: Option<u32>,
+    /// The calories of the three elves carrying the most so far, the most
+    /// first.
+    top: [u32; 3],
+}
+
+impl TopElves {
+    fn end_inventory(&mut self) {
+        let Some(total) = self.current.take() else {
+            return;
+        };
+        if let Some(index) = self.top.iter().position(|top| total > *top) {
+            self.top[index..].rotate_right(1);
+            self.top[index] = total;
+        }
+    }
+}
+
+/// Generate the calories carried by `size` elves.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..rng.range(1..=15))
+                .map(|_| rng.range(1_000..=60_000).to_string())
+                .join("\n")
+        })
+        .join("\n\n")
+        + "\n"
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        elf_calories(input)
+    }
+
+    fn part1(calories: &Self::Input<'_>) -> PartResult {
+        Ok(top_calories(calories, 1).into())
+    }
+
+    fn part2(calories: &Self::Input<'_>) -> PartResult {
+        Ok(top_calories(calories, 3).into())
+    }
+}
+
+impl Streaming for Day1 {
+    type State = TopElves;
+
+    fn read_line(elves: &mut Self::State, line: Line) -> Result<(), ParseError> {
+        if line.text.is_empty() {
+            elves.end_inventory();
+        } else {
+            elves.current = Some(add_calories(elves.current.unwrap_or_default(), &line)?);
+        }
+        Ok(())
+    }
+
+    fn finish(elves: &mut Self::State) -> Result<(), ParseError> {
+        elves.end_inventory();
+        Ok(())
+    }
+
+    fn answer(elves: &Self::State, part: Part) -> PartResult {
+        match part {
+            Part::One => Ok(elves.top[0].into()),
+            Part::Two => Ok(top_calories(&elves.top, 3).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../data/day1/example.txt");
+
+    #[test]
+    fn part1_example() {
+        let calories = elf_calories(EXAMPLE).unwrap();
+        assert_eq!(top_calories(&calories, 1), 24000);
+    }
+
+    #[test]
+    fn part2_example() {
+        let calories = elf_calories(EXAMPLE).unwrap();
+        assert_eq!(top_calories(&calories, 3), 45000);
+    }
+
+    #[test]
+    fn heavy_elves() {
+        let calories = elf_calories("4000000000\n\n4000000000\n").unwrap();
+        assert_eq!(top_calories(&calories, 3), 8_000_000_000);
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{
+    generate::Rng,
+    image::{self, Image},
+    parsing::{lines, Line},
+    solution::{PartResult, Solution, Streaming},
+    visualize::Visualize,
+    ParseError, Part,
+};
+
+const CRT_WIDTH: i32 = 40;
+
+#[derive(Debug)]
+pub enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+#[derive(Debug)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+}
+
+pub fn parse_instruction(line: &Line) -> Result<Instruction, ParseError> {
+    let mut words = line.words();
+    let instruction = match words.keyword(&["noop", "addx"])? {
+        "noop" => Instruction::Noop,
+        _ => Instruction::Addx(words.integer("a number to add")?),
+    };
+    words.end()?;
+    Ok(instruction)
+}
+
+/// The register after the instruction on the line, which has to stay a 32-bit
+/// number.
+fn add_to_register(
+    register: i32,
+    instruction: &Instruction,
+    line: &Line,
+) -> Result<i32, ParseError> {
+    match instruction {
+        Instruction::Noop => Ok(register),
+        Instruction::Addx(amount) => register.checked_add(*amount).ok_or_else(|| {
+            let amount = line.text.rsplit(' ').next().unwrap_or(line.text);
+            line.error(
+                amount,
+                format!(
+                    "an amount that keeps X between {} and {}",
+                    i32::MIN,
+                    i32::MAX
+                ),
+            )
+        }),
+    }
+}
+
+impl TryFrom<&str> for Program {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut instructions = Vec::new();
+        let mut register = 1;
+        for line in lines(input) {
+            let instruction = parse_instruction(&line)?;
+            register = add_to_register(register, &instruction, &line)?;
+            instructions.push(instruction);
+        }
+        if instructions.is_empty() {
+            return Err(ParseError::new(1, 1, "", "at least one instruction"));
+        }
+        Ok(Program { instructions })
+    }
+}
+
+pub struct Cpu<'a> {
+    pub instructions: &'a [Instruction],
+    pub current_instruction: usize,
+    pub instruction_cycle: u32,
+    pub register: i32,
+}
+
+impl<'a> Cpu<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        Self {
+            instructions,
+            current_instruction: 0,
+            instruction_cycle: 0,
+            register: 1,
+        }
+    }
+
+    /// Run a single cycle, which fails once there are no instructions left or
+    /// when X no longer fits.
+    pub fn run_cycle(&mut self) -> Result<(), &'static str> {
+        match self.instructions[self.current_instruction] {
+            Instruction::Noop => {
+                if self.current_instruction + 1 < self.instructions.len() {
+                    self.current_instruction += 1;
+                    Ok(())
+                } else {
+                    Err("No instructions left.")
+                }
+            }
+            Instruction::Addx(amount) => {
+                if self.instruction_cycle == 0 {
+                    self.instruction_cycle += 1;
+                    Ok(())
+                } else if self.current_instruction + 1 < self.instructions.len() {
+                    self.current_instruction += 1;
+                    self.instruction_cycle = 0;
+                    self.register = self
+                        .register
+                        .checked_add(amount)
+                        .ok_or("X doesn't fit in 32 bits.")?;
+                    Ok(())
+                } else {
+                    Err("No instructions left.")
+                }
+            }
+        }
+    }
+}
+
+/// The sum of the signal strengths during the 20th, 60th, 100th, ... cycles.
+pub fn signal_strength(program: &Program) -> Result<i64, &'static str> {
+    let mut cpu = Cpu::new(&program.instructions);
+
+    let mut cycle = 1;
+    let mut result = 0;
+    while cpu.run_cycle().is_ok() {
+        cycle += 1;
+        log::trace!("amount: {cycle}");
+        if (cycle + 20) % 40 == 0 {
+            log::trace!("added result");
+            result = add_signal_strength(result, cycle, cpu.register)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Add the signal strength during a cycle to the sum of the earlier ones.
+fn add_signal_strength(sum: i64, cycle: i32, register: i32) -> Result<i64, &'static str> {
+    sum.checked_add(i64::from(cycle) * i64::from(register))
+        .ok_or("The signal strength doesn't fit in 64 bits.")
+}
+
+/// The image drawn on the CRT while running the program.
+///
+/// The sprite is three pixels wide and centered on the register, every cycle
+/// draws a single pixel and the screen is 40 pixels wide.
+pub fn render_crt(program: &Program) -> String {
+    let mut cpu = Cpu::new(&program.instructions);
+    let mut screen = String::new();
+
+    let mut cycle = 1;
+    draw_pixel(&mut screen, cycle, cpu.register);
+    while cpu.run_cycle().is_ok() {
+        cycle += 1;
+        draw_pixel(&mut screen, cycle, cpu.register);
+    }
+
+    screen
+}
+
+/// Draw the pixel of a cycle, starting a new line of the screen every 40
+/// pixels.
+fn draw_pixel(screen: &mut String, cycle: i32, register: i32) {
+    let position = (cycle - 1) % CRT_WIDTH;
+    if position == 0 && cycle > 1 {
+        screen.push('\n');
+    }
+    if register.abs_diff(position) <= 1 {
+        screen.push('#');
+    } else {
+        screen.push('.');
+    }
+}
+
+/// The signal strength and the screen, worked out while running the
+/// instructions as they are read.
+pub struct Trace {
+    /// The cycles that are done.
+    cycle: i32,
+    register: i32,
+    /// The sum of the signal strengths, or `None` once it no longer fits.
+    signal_strength: Option<i64>,
+    screen: String,
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self {
+            cycle: 0,
+            register: 1,
+            signal_strength: Some(0),
+            screen: String::new(),
+        }
+    }
+}
+
+impl Trace {
+    /// Run an instruction, which can't take X out of the 32-bit numbers.
+    fn execute(&mut self, instruction: &Instruction) {
+        let cycles = match instruction {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        };
+        // The register only changes once the instruction is done.
+        for _ in 0..cycles {
+            self.cycle += 1;
+            if (self.cycle + 20) % 40 == 0 {
+                self.signal_strength = self
+                    .signal_strength
+                    .and_then(|sum| add_signal_strength(sum, self.cycle, self.register).ok());
+            }
+            draw_pixel(&mut self.screen, self.cycle, self.register);
+        }
+        if let Instruction::Addx(amount) = instruction {
+            self.register += amount;
+        }
+    }
+}
+
+/// The CRT drawing the image a pixel at a time.
+pub struct CrtVisualization {
+    /// The register during every cycle.
+    registers: Vec<i32>,
+    /// The amount of pixels that are drawn.
+    drawn: usize,
+}
+
+impl Visualize for CrtVisualization {
+    fn frame(&self) -> String {
+        let width = CRT_WIDTH as usize;
+        // The sprite where the next pixel is drawn, or where it was last.
+        let register = self.registers[self.drawn.min(self.registers.len() - 1)];
+        let sprite = (0..CRT_WIDTH)
+            .map(|position| {
+                if register.abs_diff(position) <= 1 {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+        let mut frame = format!("Sprite: {sprite}\n\n");
+        for row in 0..self.registers.len().div_ceil(width) {
+            for pixel in row * width..((row + 1) * width).min(self.registers.len()) {
+                let position = (pixel % width) as i32;
+                frame.push(if pixel >= self.drawn {
+                    ' '
+                } else if self.registers[pixel].abs_diff(position) <= 1 {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            frame.push('\n');
+        }
+        frame
+            + &format!(
+                "\nCycle {} of {}, X = {register}.",
+                self.drawn,
+                self.registers.len()
+            )
+    }
+
+    /// The screen with the lit pixels in amber, the dark ones in dark gray
+    /// and the ones that aren't drawn yet in black.
+    fn image(&self) -> Option<Image> {
+        let width = CRT_WIDTH as usize;
+        let height = self.registers.len().div_ceil(width);
+        Some(Image::from_fn(width, height, |x, y| {
+            let pixel = y * width + x;
+            match self.registers.get(pixel) {
+                Some(register) if pixel < self.drawn => {
+                    if register.abs_diff(x as i32) <= 1 {
+                        [255, 176, 0]
+                    } else {
+                        [40, 40, 40]
+                    }
+                }
+                _ => image::BLACK,
+            }
+        }))
+    }
+
+    fn step(&mut self) -> bool {
+        self.drawn < self.registers.len() && {
+            self.drawn += 1;
+            true
+        }
+    }
+
+    fn steps(&self) -> usize {
+        self.registers.len()
+    }
+}
+
+/// The CRT before it draws anything.
+fn crt_visualization(input: &str) -> Result<CrtVisualization, ParseError> {
+    let program = Program::try_from(input)?;
+    let mut cpu = Cpu::new(&program.instructions);
+    let mut registers = vec![cpu.register];
+    while cpu.run_cycle().is_ok() {
+        registers.push(cpu.register);
+    }
+    Ok(CrtVisualization {
+        registers,
+        drawn: 0,
+    })
+}
+
+/// Show the CRT drawing the image.
+pub fn visualize(input: &str) -> Result<Box<dyn Visualize>, ParseError> {
+    Ok(Box::new(crt_visualization(input)?))
+}
+
+/// Draw the screen after the program ran.
+pub fn draw(input: &str) -> Result<Vec<(&'static str, Image)>, ParseError> {
+    let mut crt = crt_visualization(input)?;
+    while crt.step() {}
+    Ok(vec![("crt", crt.image().expect("The CRT can be drawn."))])
+}
+
+/// Generate a program of `size` instructions. Every `addx` moves the sprite
+/// to somewhere on the screen, so there is always something to see.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut register = 1;
+    (0..size.max(1))
+        .map(|_| {
+            let target = rng.range(0..=CRT_WIDTH as u64 - 1) as i32;
+            if rng.chance(1, 3) || target == register {
+                "noop\n".to_string()
+            } else {
+                let value = target - register;
+                register = target;
+                format!("addx {value}\n")
+            }
+        })
+        .collect()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = Program;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Program::try_from(input)
+    }
+
+    fn part1(program: &Self::Input<'_>) -> PartResult {
+        Ok(signal_strength(program)?.into())
+    }
+
+    fn part2(program: &Self::Input<'_>) -> PartResult {
+        Ok(render_crt(program).into())
+    }
+}
+
+impl Streaming for Day10 {
+    type State = Trace;
+
+    fn read_line(trace: &mut Self::State, line: Line) -> Result<(), ParseError> {
+        let instruction = parse_instruction(&line)?;
+        add_to_register(trace.register, &instruction, &line)?;
+        trace.execute(&instruction);
+        Ok(())
+    }
+
+    fn finish(trace: &mut Self::State) -> Result<(), ParseError> {
+        if trace.cycle == 0 {
+            return Err(ParseError::new(1, 1, "", "at least one instruction"));
+        }
+        Ok(())
+    }
+
+    fn answer(trace: &Self::State, part: Part) -> PartResult {
+        match part {
+            Part::One => Ok(trace
+                .signal_strength
+                .ok_or("The signal strength doesn't fit in 64 bits.")?
+                .into()),
+            Part::Two => Ok(trace.screen.clone().into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test1() {
+        let input = include_str!("../data/day10/example.txt");
+
+        let program = Program::try_from(input).unwrap();
+
+        let result = signal_strength(&program);
+
+        println!("signal: {result:?}");
+        assert_eq!(result, Ok(13140));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = include_str!("../data/day10/example.txt");
+
+        let program = Program::try_from(input).unwrap();
+
+        assert_eq!(
+            render_crt(&program),
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+        );
+
+        let mut visualization = visualize(input).unwrap();
+        while visualization.step() {}
+        let frame = visualization.frame();
+        assert!(frame.contains(&format!("\n\n{}\n\n", render_crt(&program))));
+        assert!(frame.ends_with("Cycle 240 of 240, X = 17."), "{frame}");
+
+        let image = &draw(input).unwrap()[0].1;
+        assert_eq!((image.width(), image.height()), (40, 6));
+        assert_eq!(image.get(1, 0), [255, 176, 0]);
+        assert_eq!(image.get(2, 0), [40, 40, 40]);
+    }
+
+    #[test]
+    fn register_overflow() {
+        let input = "addx 2147483647\naddx 1\nnoop\n";
+        let error = Program::try_from(input).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 6));
+        let streamed = crate::solution::stream::<Day10>(10, &mut input.as_bytes(), &[]).unwrap();
+        assert_eq!(streamed.parse_error, Some(error));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    generate::Rng,
+    parsing::{lines, Line},
+    solution::{PartResult, Solution},
+    ParseError,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RPSChoice {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl RPSChoice {
+    pub fn would_win_from(choice: RPSChoice) -> RPSChoice {
+        match choice {
+            RPSChoice::Rock => Self::Paper,
+            RPSChoice::Paper => RPSChoice::Scissors,
+            RPSChoice::Scissors => Self::Rock,
+        }
+    }
+
+    pub fn would_lose_from(choice: RPSChoice) -> RPSChoice {
+        match choice {
+            RPSChoice::Rock => Self::Scissors,
+            RPSChoice::Paper => Self::Rock,
+            RPSChoice::Scissors => Self::Paper,
+        }
+    }
+}
+
+/// The second column of the strategy guide, what it means depends on the part.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Column {
+    X,
+    Y,
+    Z,
+}
+
+/// A single line of the strategy guide.
+#[derive(Debug)]
+pub struct Round {
+    pub opponent: RPSChoice,
+    pub column: Column,
+}
+
+impl Round {
+    /// The game when the second column is the choice to make.
+    pub fn as_choice(&self) -> Game {
+        Game {
+            player1: self.opponent,
+            player2: match self.column {
+                Column::X => RPSChoice::Rock,
+                Column::Y => RPSChoice::Paper,
+                Column::Z => RPSChoice::Scissors,
+            },
+        }
+    }
+
+    /// The game when the second column is the outcome the game needs to have.
+    pub fn as_outcome(&self) -> Game {
+        Game {
+            player1: self.opponent,
+            player2: match self.column {
+                Column::X => RPSChoice::would_lose_from(self.opponent),
+                Column::Y => self.opponent,
+                Column::Z => RPSChoice::would_win_from(self.opponent),
+            },
+        }
+    }
+}
+
+pub struct Game {
+    pub player1: RPSChoice,
+    pub player2: RPSChoice,
+}
+
+impl Game {
+    fn _player1_score(&self) -> u32 {
+        let mut score = 0;
+        score += match self.player1 {
+            RPSChoice::Rock => 1,
+            RPSChoice::Paper => 2,
+            RPSChoice::Scissors => 3,
+        };
+        if self.player1 == self.player2 {
+            score += 3;
+        } else if (self.player1 == RPSChoice::Rock && self.player2 == RPSChoice::Scissors)
+            || (self.player1 == RPSChoice::Paper && self.player2 == RPSChoice::Rock)
+            || (self.player1 == RPSChoice::Scissors && self.player2 == RPSChoice::Paper)
+        {
+            score += 6;
+        }
+        score
+    }
+
+    pub fn player2_score(&self) -> u32 {
+        let mut score = 0;
+        score += match self.player2 {
+            RPSChoice::Rock => 1,
+            RPSChoice::Paper => 2,
+            RPSChoice::Scissors => 3,
+        };
+        let other_score = if self.player1 == self.player2 {
+            3
+        } else if (self.player1 == RPSChoice::Rock && self.player2 == RPSChoice::Scissors)
+            || (self.player1 == RPSChoice::Paper && self.player2 == RPSChoice::Rock)
+            || (self.player1 == RPSChoice::Scissors && self.player2 == RPSChoice::Paper)
+        {
+            6
+        } else {
+            0
+        };
+        score += 6 - other_score;
+        score
+    }
+}
+
+/// A round like `A Y`, of the opponent's choice and the second column.
+pub fn parse_round(line: &Line) -> Result<Round, ParseError> {
+    let mut words = line.words();
+    let opponent = match words.keyword(&["A", "B", "C"])? {
+        "A" => RPSChoice::Rock,
+        "B" => RPSChoice::Paper,
+        _ => RPSChoice::Scissors,
+    };
+    let column = match words.keyword(&["X", "Y", "Z"])? {
+        "X" => Column::X,
+        "Y" => Column::Y,
+        _ => Column::Z,
+    };
+    words.end()?;
+    Ok(Round { opponent, column })
+}
+
+pub fn parse_rounds(input: &str) -> Result<Vec<Round>, ParseError> {
+    lines(input).map(|line| parse_round(&line)).collect()
+}
+
+/// The total score of player 2 over all the games.
+pub fn total_score(games: impl Iterator<Item = Game>) -> u32 {
+    let mut total = 0;
+
+    for game in games {
+        total += game.player2_score();
+    }
+
+    total
+}
+
+/// Generate a strategy guide with `size` rounds.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.choose(&['A', 'B', 'C']),
+                rng.choose(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<Round>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_rounds(input)
+    }
+
+    fn part1(rounds: &Self::Input<'_>) -> PartResult {
+        Ok(total_score(rounds.iter().map(Round::as_choice)).into())
+    }
+
+    fn part2(rounds: &Self::Input<'_>) -> PartResult {
+        Ok(total_score(rounds.iter().map(Round::as_outcome)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../data/day2/example.txt");
+
+    #[test]
+    fn part1_example() {
+        let rounds = parse_rounds(EXAMPLE).unwrap();
+        assert_eq!(total_score(rounds.iter().map(Round::as_choice)), 15);
+    }
+
+    #[test]
+    fn part2_example() {
+        let rounds = parse_rounds(EXAMPLE).unwrap();
+        assert_eq!(total_score(rounds.iter().map(Round::as_outcome)), 12);
+    }
+
+    #[test]
+    fn reject_unknown_choices() {
+        assert_eq!(
+            parse_rounds("A Y\nB W\n").unwrap_err(),
+            ParseError::new(2, 3, "W", "X, Y or Z")
+        );
+        assert_eq!(
+            parse_rounds("A  Y\n").unwrap_err(),
+            ParseError::new(1, 3, "", "X, Y or Z")
+        );
+    }
+}
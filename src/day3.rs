@@ -0,0 +1,204 @@
+use itertools::Itertools;
+
+use crate::{
+    generate::Rng,
+    parsing::lines,
+    solution::{PartResult, Solution},
+    ParseError,
+};
+
+pub trait RuckSack {
+    fn first_compartment(&self) -> &str;
+
+    fn second_compartment(&self) -> &str;
+
+    fn shared_item_accross_compartments(&self) -> Result<char, &'static str>;
+}
+
+pub fn item_to_priority(item: char) -> Result<u32, &'static str> {
+    if item.is_ascii_lowercase() {
+        Ok((item as u8 - b'a' + 1) as u32)
+    } else if item.is_ascii_uppercase() {
+        Ok((item as u8 - b'A' + 27) as u32)
+    } else {
+        Err("Not a valid item.")
+    }
+}
+
+pub fn shared_character(elves: &[&str]) -> Result<char, &'static str> {
+    if elves.len() < 2 {
+        Err("Need at least two elves.")
+    } else {
+        let first_elf = &elves[0];
+        let mut found = None;
+        first_elf.chars().for_each(|char| {
+            if elves[1..].iter().all(|string| string.contains(char)) {
+                found = Some(char)
+            }
+        });
+        if let Some(char) = found {
+            Ok(char)
+        } else {
+            Err("No shared item.")
+        }
+    }
+}
+
+impl RuckSack for str {
+    fn first_compartment(&self) -> &str {
+        self.split_at(self.len() / 2).0
+    }
+
+    fn second_compartment(&self) -> &str {
+        self.split_at(self.len() / 2).1
+    }
+
+    fn shared_item_accross_compartments(&self) -> Result<char, &'static str> {
+        log::trace!(
+            "First: {}, Second: {}",
+            self.first_compartment(),
+            self.second_compartment()
+        );
+        self.first_compartment()
+            .chars()
+            .find(|item| self.second_compartment().contains(*item))
+            .ok_or("No item is in both compartments.")
+    }
+}
+
+/// Parse the contents of every rucksack, which has to be made up of items that
+/// have a priority and be evenly divided over both compartments.
+pub fn parse_rucksacks(input: &str) -> Result<Vec<&str>, ParseError> {
+    lines(input)
+        .map(|line| {
+            if let Some((position, item)) = line
+                .text
+                .char_indices()
+                .find(|(_, item)| item_to_priority(*item).is_err())
+            {
+                Err(line.error(
+                    &line.text[position..position + item.len_utf8()],
+                    "an item from a to z or A to Z",
+                ))
+            } else if line.text.len() % 2 != 0 {
+                Err(line.error(line.text, "an even amount of items"))
+            } else {
+                Ok(line.text)
+            }
+        })
+        .collect()
+}
+
+/// The total priority of the items that are in both compartments of every
+/// rucksack.
+pub fn total_priority(rucksacks: &[&str]) -> Result<u32, &'static str> {
+    let mut total_priority = 0;
+
+    for rucksack in rucksacks {
+        let shared = rucksack.shared_item_accross_compartments()?;
+        let priority = item_to_priority(shared)?;
+        log::trace!("Shared: {} with value {}.", shared, priority);
+        total_priority += priority;
+    }
+
+    Ok(total_priority)
+}
+
+/// The total priority of the badges, the items shared by every group of three
+/// elves.
+pub fn total_group_priority(rucksacks: &[&str]) -> Result<u32, &'static str> {
+    let mut total = 0;
+
+    for group in rucksacks.chunks(3) {
+        if group.len() != 3 {
+            return Err("The elves don't split into groups of three.");
+        }
+        total += item_to_priority(shared_character(group)?)?;
+    }
+
+    Ok(total)
+}
+
+/// Generate `size` rucksacks, rounded up to whole groups of three. Every
+/// rucksack has exactly one item in both compartments, and every group has
+/// exactly one item in all three rucksacks.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut rucksacks = String::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        let mut items = ('a'..='z').chain('A'..='Z').collect_vec();
+        rng.shuffle(&mut items);
+        let badge = items[0];
+        // Every elf gets items of its own, so the badge is the only item the
+        // group shares.
+        for items in items[1..].chunks(17) {
+            let shared = items[0];
+            let (first_items, second_items) = items[1..].split_at(8);
+            let length = rng.range_usize(2..=12);
+            let mut first = vec![shared, badge];
+            first.extend((2..length).map(|_| *rng.choose(first_items)));
+            let mut second = vec![shared];
+            second.extend((1..length).map(|_| *rng.choose(second_items)));
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            rucksacks.extend(first.into_iter().chain(second));
+            rucksacks.push('\n');
+        }
+    }
+    rucksacks
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Vec<&'a str>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_rucksacks(input)
+    }
+
+    fn part1(rucksacks: &Self::Input<'_>) -> PartResult {
+        Ok(total_priority(rucksacks)?.into())
+    }
+
+    fn part2(rucksacks: &Self::Input<'_>) -> PartResult {
+        Ok(total_group_priority(rucksacks)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../data/day3/example.txt");
+
+    #[test]
+    fn part1_example() {
+        let rucksacks = parse_rucksacks(EXAMPLE).unwrap();
+        assert_eq!(total_priority(&rucksacks), Ok(157));
+    }
+
+    #[test]
+    fn part2_example() {
+        let rucksacks = parse_rucksacks(EXAMPLE).unwrap();
+        assert_eq!(total_group_priority(&rucksacks), Ok(70));
+    }
+
+    #[test]
+    fn unsolvable_rucksacks() {
+        assert!(total_priority(&parse_rucksacks("ab\n").unwrap()).is_err());
+        assert!(total_group_priority(&parse_rucksacks("aa\naa\naa\naa\n").unwrap()).is_err());
+        assert!(total_group_priority(&parse_rucksacks("ab\ncd\nef\n").unwrap()).is_err());
+    }
+
+    #[test]
+    fn reject_odd_rucksacks() {
+        assert_eq!(
+            parse_rucksacks("abab\naé\n").unwrap_err(),
+            ParseError::new(2, 2, "é", "an item from a to z or A to Z")
+        );
+        assert_eq!(
+            parse_rucksacks("abc\n").unwrap_err(),
+            ParseError::new(1, 1, "abc", "an even amount of items")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::fmt::Display;
+
+use crate::{
+    generate::Rng,
+    interval::Interval,
+    parsing::{lines, Line},
+    solution::{PartResult, Solution, Streaming},
+    ParseError, Part,
+};
+
+#[derive(Debug)]
+pub struct Group {
+    pub first: Interval<u32>,
+    pub second: Interval<u32>,
+}
+
+impl Group {
+    pub fn contains_total_overlap(&self) -> bool {
+        self.first.covers(&self.second) || self.second.covers(&self.first)
+    }
+
+    pub fn contains_overlap(&self) -> bool {
+        self.first.overlaps(&self.second)
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sections =
+            |interval: &Interval<u32>| format!("{}-{}", interval.start, interval.end - 1);
+        write!(
+            f,
+            "Elf 1: {}\nElf 2: {}",
+            sections(&self.first),
+            sections(&self.second)
+        )
+    }
+}
+
+/// Parse a line of two section assignments like `2-4,6-8`.
+pub fn parse_line(line: &Line) -> Result<Group, ParseError> {
+    let mut scanner = line.scan();
+    let first_start = scanner.integer("a section number")?;
+    scanner.separator('-')?;
+    let first_end = scanner.integer("a section number")?;
+    scanner.separator(',')?;
+    let second_start = scanner.integer("a section number")?;
+    scanner.separator('-')?;
+    let second_end = scanner.integer("a section number")?;
+    scanner.end()?;
+    let assignment = |first, last| {
+        if first > last {
+            return Err(line.error(line.text, "assignments that don't end before they start"));
+        }
+        Interval::try_inclusive(first, last)
+            .ok_or_else(|| line.error(line.text, format!("sections below {}", u32::MAX)))
+    };
+    Ok(Group {
+        first: assignment(first_start, first_end)?,
+        second: assignment(second_start, second_end)?,
+    })
+}
+
+pub fn parse_groups(input: &str) -> Result<Vec<Group>, ParseError> {
+    lines(input).map(|line| parse_line(&line)).collect()
+}
+
+/// The amount of groups where one elf's assignment fully contains the other's.
+pub fn total_overlaps(groups: &[Group]) -> usize {
+    groups
+        .iter()
+        .filter(|group| group.contains_total_overlap())
+        .count()
+}
+
+/// The amount of groups where the assignments overlap at all.
+pub fn total_partial_overlaps(groups: &[Group]) -> usize {
+    groups
+        .iter()
+        .filter(|group| group.contains_overlap())
+        .count()
+}
+
+/// The amount of groups that overlap, counted while reading them a line at a
+/// time.
+#[derive(Debug, Default)]
+pub struct Overlaps {
+    pub total: usize,
+    pub partial: usize,
+}
+
+/// Generate `size` pairs of section assignments.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let mut sections = || {
+                let start = rng.range(1..=99);
+                (start, rng.range(start..=99))
+            };
+            let (first, second) = (sections(), sections());
+            format!("{}-{},{}-{}\n", first.0, first.1, second.0, second.1)
+        })
+        .collect()
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = Vec<Group>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_groups(input)
+    }
+
+    fn part1(groups: &Self::Input<'_>) -> PartResult {
+        Ok(total_overlaps(groups).into())
+    }
+
+    fn part2(groups: &Self::Input<'_>) -> PartResult {
+        Ok(total_partial_overlaps(groups).into())
+    }
+}
+
+impl Streaming for Day4 {
+    type State = Overlaps;
+
+    fn read_line(overlaps: &mut Self::State, line: Line) -> Result<(), ParseError> {
+        let group = parse_line(&line)?;
+        overlaps.total += usize::from(group.contains_total_overlap());
+        overlaps.partial += usize::from(group.contains_overlap());
+        Ok(())
+    }
+
+    fn answer(overlaps: &Self::State, part: Part) -> PartResult {
+        match part {
+            Part::One => Ok(overlaps.total.into()),
+            Part::Two => Ok(overlaps.partial.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../data/day4/example.txt");
+
+    #[test]
+    fn part1_example() {
+        let groups = parse_groups(EXAMPLE).unwrap();
+        assert_eq!(total_overlaps(&groups), 2);
+    }
+
+    #[test]
+    fn part2_example() {
+        let groups = parse_groups(EXAMPLE).unwrap();
+        assert_eq!(total_partial_overlaps(&groups), 4);
+    }
+
+    #[test]
+    fn display_group() {
+        let groups = parse_groups(EXAMPLE).unwrap();
+        assert_eq!(groups[3].to_string(), "Elf 1: 2-8\nElf 2: 3-7");
+    }
+
+    #[test]
+    fn reject_backwards_assignments() {
+        assert_eq!(
+            parse_groups("2-4,6-8\n6-4,1-2").unwrap_err(),
+            ParseError::new(
+                2,
+                1,
+                "6-4,1-2",
+                "assignments that don't end before they start"
+            )
+        );
+    }
+
+    #[test]
+    fn reject_wrong_separators() {
+        assert_eq!(
+            parse_groups("2 4 6 8").unwrap_err(),
+            ParseError::new(1, 2, " ", "'-'")
+        );
+        assert_eq!(
+            parse_groups("1,2,3,4").unwrap_err(),
+            ParseError::new(1, 2, ",", "'-'")
+        );
+        assert_eq!(
+            parse_groups("2-4-6-8").unwrap_err(),
+            ParseError::new(1, 4, "-", "','")
+        );
+        assert_eq!(
+            parse_groups("2-4,6-8,").unwrap_err(),
+            ParseError::new(1, 8, ",", "the end of the line")
+        );
+    }
+}
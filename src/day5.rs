@@ -0,0 +1,560 @@
+use itertools::Itertools;
+use std::{
+    fmt::Display,
+    num::NonZeroUsize,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    generate::Rng,
+    parsing::{fixed_width_columns, integers, paragraphs, Line},
+    solution::{PartResult, Solution},
+    visualize::Visualize,
+    ParseError,
+};
+
+#[derive(Debug, Clone)]
+pub struct Crate(pub char);
+
+impl Deref for Crate {
+    type Target = char;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Crate {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Stack(pub Vec<Crate>);
+
+impl Deref for Stack {
+    type Target = Vec<Crate>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Stack {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cargo(pub Vec<Stack>);
+
+impl Deref for Cargo {
+    type Target = Vec<Stack>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Cargo {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct Operation {
+    pub amount: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug)]
+pub struct Operations(pub Vec<Operation>);
+
+impl Deref for Operations {
+    type Target = Vec<Operation>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Operations {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Display for Crate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for Cargo {
+    /// Draw the stacks like the drawing in the input.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self
+            .iter()
+            .map(|stack| stack.len())
+            .max()
+            .unwrap_or_default();
+        for row in (0..height).rev() {
+            let line = self
+                .iter()
+                .map(|stack| {
+                    stack
+                        .get(row)
+                        .map_or("   ".to_string(), |item| format!("[{item}]"))
+                })
+                .join(" ");
+            writeln!(f, "{line}")?;
+        }
+        write!(
+            f,
+            "{}",
+            (1..=self.len()).map(|stack| format!(" {stack} ")).join(" ")
+        )
+    }
+}
+
+impl TryFrom<&[Line<'_>]> for Cargo {
+    type Error = ParseError;
+
+    fn try_from(drawing: &[Line<'_>]) -> Result<Self, Self::Error> {
+        // Omit the last line which only numbers the stacks.
+        let Some((_, rows)) = drawing.split_last() else {
+            return Err(ParseError::new(
+                1,
+                1,
+                "",
+                "a drawing of the stacks of crates",
+            ));
+        };
+        // Every crate takes up four characters, with the space after it.
+        let cargo = rows
+            .iter()
+            .map(|line| {
+                fixed_width_columns(line, 4)
+                    .into_iter()
+                    .map(|column| match column.chars().collect::<Vec<_>>()[..] {
+                        ['[', value, ']'] | ['[', value, ']', ' '] if value.is_alphabetic() => {
+                            Ok(Some(Crate(value)))
+                        }
+                        [' ', ' ', ' '] | [' ', ' ', ' ', ' '] => Ok(None),
+                        _ => Err(line.error(column, "a crate like [A] or no crate")),
+                    })
+                    .collect::<Result<Vec<Option<Crate>>, ParseError>>()
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        let stacks = cargo.iter().map(Vec::len).max().unwrap_or(0);
+        let mut crates: Vec<Stack> = Vec::new();
+        for stack_item in 0..stacks {
+            let mut stack: Vec<Crate> = Vec::new();
+            for crate_item in 0..cargo.len() {
+                if let Some(Some(item)) = &cargo[cargo.len() - 1 - crate_item].get(stack_item) {
+                    stack.push(item.clone());
+                }
+            }
+            crates.push(Stack(stack));
+        }
+        Ok(Cargo(crates))
+    }
+}
+
+impl TryFrom<&[Line<'_>]> for Operations {
+    type Error = ParseError;
+
+    fn try_from(procedure: &[Line<'_>]) -> Result<Self, Self::Error> {
+        procedure
+            .iter()
+            .map(|line| {
+                let values = integers::<NonZeroUsize>(line, "a positive number")?;
+                if let [amount, from, to] = values[..] {
+                    Ok(Operation {
+                        amount: amount.get(),
+                        from: from.get(),
+                        to: to.get(),
+                    })
+                } else {
+                    Err(line.error(line.text, "move <amount> from <stack> to <stack>"))
+                }
+            })
+            .collect::<Result<Vec<Operation>, ParseError>>()
+            .map(Self)
+    }
+}
+
+pub struct CrateMover9000<'a> {
+    crates: &'a mut Cargo,
+    operations: &'a Operations,
+}
+
+impl<'a> CrateMover9000<'a> {
+    pub fn new(crates: &'a mut Cargo, operations: &'a Operations) -> Self {
+        Self { crates, operations }
+    }
+
+    pub fn execute(&mut self) {
+        self.operations.iter().for_each(|single| {
+            self.move_crates(single);
+        });
+    }
+
+    fn move_crates(&mut self, operation: &Operation) {
+        for _ in 0..operation.amount {
+            let popped_crate = self.crates[operation.from - 1]
+                .pop()
+                .expect("Ran out of crates to remove.");
+            self.crates[operation.to - 1].push(popped_crate);
+        }
+    }
+}
+
+pub struct CrateMover9001<'a> {
+    crates: &'a mut Cargo,
+    operations: &'a Operations,
+}
+
+impl<'a> CrateMover9001<'a> {
+    pub fn new(crates: &'a mut Cargo, operations: &'a Operations) -> Self {
+        Self { crates, operations }
+    }
+
+    pub fn execute(&mut self) {
+        self.operations.iter().for_each(|single| {
+            self.move_crates(single);
+        });
+    }
+
+    fn move_crates(&mut self, operation: &Operation) {
+        let mut reverse = Vec::new();
+        for _ in 0..operation.amount {
+            let popped_crate = self.crates[operation.from - 1]
+                .pop()
+                .expect("Ran out of crates to remove.");
+            reverse.push(popped_crate);
+        }
+        for _ in 0..operation.amount {
+            self.crates[operation.to - 1].push(reverse.pop().unwrap());
+        }
+    }
+}
+
+impl Cargo {
+    /// The crates on top of every stack, which all need one.
+    pub fn top_crates(&self) -> Result<String, String> {
+        self.iter()
+            .enumerate()
+            .map(|(index, stack)| {
+                stack
+                    .last()
+                    .map(|top| top.0)
+                    .ok_or_else(|| format!("Stack {} has no crates.", index + 1))
+            })
+            .collect()
+    }
+}
+
+/// Parse the crate drawing and the rearrangement procedure, which are
+/// separated by an empty line.
+pub fn parse_input(input: &str) -> Result<(Cargo, Operations), ParseError> {
+    let mut paragraphs = paragraphs(input);
+    let drawing = paragraphs.next().unwrap_or_default();
+    let procedure = paragraphs.flatten().collect_vec();
+    if procedure.is_empty() {
+        return Err(ParseError::new(
+            drawing.last().map_or(1, |line| line.number + 1),
+            1,
+            "",
+            "an empty line after the drawing of the crates",
+        ));
+    }
+
+    let crates = Cargo::try_from(drawing.as_slice())?;
+    let operations = Operations::try_from(procedure.as_slice())?;
+
+    // Every operation has to refer to an existing stack, and can't take
+    // more crates than the stack has by then. Both cranes move as many
+    // crates, so the stacks are as high for either.
+    let mut heights = crates.iter().map(|stack| stack.len()).collect_vec();
+    for (operation, line) in operations.iter().zip(&procedure) {
+        if operation.from > crates.len() || operation.to > crates.len() {
+            return Err(line.error(line.text, format!("stacks from 1 to {}", crates.len())));
+        }
+        let available = heights[operation.from - 1];
+        if operation.amount > available {
+            return Err(line.error(line.text, format!("an amount of at most {available}")));
+        }
+        heights[operation.from - 1] -= operation.amount;
+        heights[operation.to - 1] += operation.amount;
+    }
+
+    Ok((crates, operations))
+}
+
+/// Generate a drawing of up to nine stacks followed by `size` moves. The
+/// moves never take the last crate off a stack, so no stack runs empty.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut stacks = (0..rng.range_usize(2..=9))
+        .map(|_| {
+            (0..rng.range_usize(2..=8))
+                .map(|_| char::from(b'A' + rng.below(26) as u8))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let cargo = Cargo(
+        stacks
+            .iter()
+            .map(|stack| Stack(stack.iter().copied().map(Crate).collect()))
+            .collect(),
+    );
+    let mut input = cargo.to_string();
+    input.push_str("\n\n");
+
+    for _ in 0..size.max(1) {
+        // There are at least two crates for every stack, so at least one
+        // stack has two crates to take one from.
+        let from = loop {
+            let from = rng.index(stacks.len());
+            if stacks[from].len() > 1 {
+                break from;
+            }
+        };
+        let to = (from + rng.range_usize(1..=stacks.len() - 1)) % stacks.len();
+        let amount = rng.range_usize(1..=stacks[from].len() - 1);
+        let remaining = stacks[from].len() - amount;
+        let moved = stacks[from].split_off(remaining);
+        stacks[to].extend(moved);
+        input.push_str(&format!("move {amount} from {} to {}\n", from + 1, to + 1));
+    }
+    input
+}
+
+/// The crane rearranging the crates a move at a time, like the CrateMover
+/// 9000 does.
+pub struct CraneVisualization {
+    cargo: Cargo,
+    operations: Operations,
+    /// The amount of operations that are done.
+    done: usize,
+}
+
+impl Visualize for CraneVisualization {
+    fn frame(&self) -> String {
+        let next = match self.operations.get(self.done) {
+            Some(operation) if self.cargo[operation.from - 1].len() < operation.amount => {
+                format!(
+                    "Stuck at move {} from {} to {}, there aren't enough crates.",
+                    operation.amount, operation.from, operation.to
+                )
+            }
+            Some(operation) => format!(
+                "Next: move {} from {} to {}",
+                operation.amount, operation.from, operation.to
+            ),
+            None => "Done.".to_string(),
+        };
+        format!(
+            "{}\n\nMove {} of {}. {next}",
+            self.cargo,
+            self.done,
+            self.operations.len()
+        )
+    }
+
+    fn step(&mut self) -> bool {
+        let Some(operation) = self.operations.get(self.done) else {
+            return false;
+        };
+        if self.cargo[operation.from - 1].len() < operation.amount {
+            return false;
+        }
+        CrateMover9000::new(&mut self.cargo, &self.operations).move_crates(operation);
+        self.done += 1;
+        true
+    }
+
+    fn steps(&self) -> usize {
+        self.operations.len()
+    }
+}
+
+/// Show the crane rearranging the crates.
+pub fn visualize(input: &str) -> Result<Box<dyn Visualize>, ParseError> {
+    let (cargo, operations) = parse_input(input)?;
+    Ok(Box::new(CraneVisualization {
+        cargo,
+        operations,
+        done: 0,
+    }))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input<'a> = (Cargo, Operations);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1((crates, operations): &Self::Input<'_>) -> PartResult {
+        let mut crates = crates.clone();
+        CrateMover9000::new(&mut crates, operations).execute();
+        Ok(crates.top_crates()?.into())
+    }
+
+    fn part2((crates, operations): &Self::Input<'_>) -> PartResult {
+        let mut crates = crates.clone();
+        CrateMover9001::new(&mut crates, operations).execute();
+        Ok(crates.top_crates()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{self, agree};
+
+    const EXAMPLE: &str = include_str!("../data/day5/example.txt");
+
+    #[test]
+    fn part1_example() {
+        let (mut crates, operations) = parse_input(EXAMPLE).unwrap();
+        CrateMover9000::new(&mut crates, &operations).execute();
+        assert_eq!(crates.top_crates(), Ok("CMZ".to_string()));
+    }
+
+    #[test]
+    fn draw_cargo() {
+        let (crates, _) = parse_input(EXAMPLE).unwrap();
+        let drawing = EXAMPLE.split("\n\n").next().unwrap();
+        assert_eq!(crates.to_string(), drawing);
+
+        let mut visualization = visualize(EXAMPLE).unwrap();
+        while visualization.step() {}
+        let frame = visualization.frame();
+        assert!(
+            frame.ends_with("[D]\n[C] [M] [P]\n 1   2   3 \n\nMove 4 of 4. Done."),
+            "{frame}"
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        let (mut crates, operations) = parse_input(EXAMPLE).unwrap();
+        CrateMover9001::new(&mut crates, &operations).execute();
+        assert_eq!(crates.top_crates(), Ok("MCD".to_string()));
+    }
+
+    #[test]
+    fn impossible_moves() {
+        assert_eq!(
+            parse_input("[A]    \n 1   2 \n\nmove 1 from 1 to 2\nmove 2 from 2 to 1\n").map(|_| ()),
+            Err(ParseError::new(
+                5,
+                1,
+                "move 2 from 2 to 1",
+                "an amount of at most 1"
+            ))
+        );
+        let (mut crates, operations) =
+            parse_input("[A]    \n 1   2 \n\nmove 1 from 1 to 2\n").unwrap();
+        CrateMover9000::new(&mut crates, &operations).execute();
+        assert_eq!(
+            crates.top_crates(),
+            Err("Stack 1 has no crates.".to_string())
+        );
+    }
+
+    /// Stacks of crates, and moves of an amount from one stack to another.
+    type Procedure = (Vec<Vec<char>>, Vec<(usize, usize, usize)>);
+
+    /// Stacks and moves that never take more crates from a stack than it has.
+    fn procedure(rng: &mut Rng) -> Procedure {
+        let mut stacks = (0..rng.range_usize(1..=5))
+            .map(|_| {
+                (0..rng.range_usize(0..=6))
+                    .map(|_| rng.choose(&['A', 'B', 'C']))
+                    .copied()
+                    .collect_vec()
+            })
+            .collect_vec();
+        let start = stacks.clone();
+        let mut moves = Vec::new();
+        for _ in 0..rng.range_usize(0..=10) {
+            let (from, to) = (rng.index(stacks.len()), rng.index(stacks.len()));
+            let amount = rng.range_usize(0..=stacks[from].len());
+            let remaining = stacks[from].len() - amount;
+            let moved = stacks[from].split_off(remaining);
+            stacks[to].extend(moved);
+            moves.push((amount, from + 1, to + 1));
+        }
+        (start, moves)
+    }
+
+    /// Apply the moves to plain stacks, a crate at a time, or all crates of a
+    /// move at once. Gives nothing if a move takes crates that aren't there.
+    fn naive_rearrange((stacks, moves): &Procedure, at_once: bool) -> Option<Vec<Vec<char>>> {
+        let mut stacks = stacks.clone();
+        for &(amount, from, to) in moves {
+            if !(1..=stacks.len()).contains(&from) || !(1..=stacks.len()).contains(&to) {
+                return None;
+            }
+            let remaining = stacks[from - 1].len().checked_sub(amount)?;
+            if at_once {
+                let moved = stacks[from - 1].split_off(remaining);
+                stacks[to - 1].extend(moved);
+            } else {
+                for _ in 0..amount {
+                    let item = stacks[from - 1].pop()?;
+                    stacks[to - 1].push(item);
+                }
+            }
+        }
+        Some(stacks)
+    }
+
+    #[test]
+    fn crate_movers_match_stacks() {
+        property::check(procedure, |procedure| {
+            let (Some(one_at_a_time), Some(at_once)) = (
+                naive_rearrange(procedure, false),
+                naive_rearrange(procedure, true),
+            ) else {
+                return Ok(());
+            };
+            let (stacks, moves) = procedure;
+            let cargo = Cargo(
+                stacks
+                    .iter()
+                    .map(|stack| Stack(stack.iter().copied().map(Crate).collect()))
+                    .collect(),
+            );
+            let operations = Operations(
+                moves
+                    .iter()
+                    .map(|&(amount, from, to)| Operation { amount, from, to })
+                    .collect(),
+            );
+            let crates = |cargo: Cargo| {
+                cargo
+                    .iter()
+                    .map(|stack| stack.iter().map(|item| item.0).collect_vec())
+                    .collect_vec()
+            };
+
+            let mut cargo_9000 = cargo.clone();
+            CrateMover9000::new(&mut cargo_9000, &operations).execute();
+            agree(one_at_a_time, crates(cargo_9000))?;
+            let mut cargo_9001 = cargo;
+            CrateMover9001::new(&mut cargo_9001, &operations).execute();
+            agree(at_once, crates(cargo_9001))
+        });
+    }
+}
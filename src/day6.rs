@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{
+    generate::Rng,
+    parsing::lines,
+    solution::{PartResult, Solution},
+    ParseError,
+};
+
+const HEADER_SIZE: usize = 4;
+const START_OF_MESSAGE_HEADER_SIZE: usize = 14;
+
+pub struct ElfMessageParser<'a> {
+    pub data: &'a str,
+}
+
+impl<'a> ElfMessageParser<'a> {
+    /// The header as specified in the hyper elf transfer protocol v6.
+    ///
+    /// Returns all the data until the header has been read, which is all
+    /// the data up to and including the first occurrence of 4 unique
+    /// characters.
+    pub fn header(&self) -> Result<&'a str, &'static str> {
+        self.through_marker(HEADER_SIZE)
+            .ok_or("Couldn't find header.")
+    }
+
+    /// The start of message header as specified in the hyper elf transfer
+    /// protocol v6.
+    ///
+    /// Returns all the data until the header has been read, which is all
+    /// the data up to and including the first occurrence of 14 unique
+    /// characters.
+    pub fn start_of_message_header(&self) -> Result<&'a str, &'static str> {
+        self.through_marker(START_OF_MESSAGE_HEADER_SIZE)
+            .ok_or("Couldn't find start of message header.")
+    }
+
+    /// All the data up to and including the first `size` unique characters
+    /// in a row.
+    fn through_marker(&self, size: usize) -> Option<&'a str> {
+        // Instead of checking every window again, keep the start of the
+        // longest run of unique characters that ends at the current one.
+        let mut last_seen = HashMap::new();
+        let mut start = 0;
+        for (index, (position, char)) in self.data.char_indices().enumerate() {
+            if let Some(previous) = last_seen.insert(char, index) {
+                start = start.max(previous + 1);
+            }
+            if index + 1 - start == size {
+                return Some(&self.data[..position + char.len_utf8()]);
+            }
+        }
+        None
+    }
+}
+
+/// Parse the datastream buffer, which is a single line of lowercase letters.
+pub fn parse_datastream(input: &str) -> Result<&str, ParseError> {
+    let mut lines = lines(input);
+    let Some(datastream) = lines.next() else {
+        return Ok("");
+    };
+    if let Some((position, char)) = datastream
+        .text
+        .char_indices()
+        .find(|(_, char)| !char.is_ascii_lowercase())
+    {
+        return Err(datastream.error(
+            &datastream.text[position..position + char.len_utf8()],
+            "a lowercase letter",
+        ));
+    }
+    if let Some(line) = lines.next() {
+        return Err(line.error(line.text, "the end of the input"));
+    }
+    Ok(datastream.text)
+}
+
+/// Generate a datastream of about `size` characters. It starts with
+/// characters from only a few letters, so markers are rare, followed by a
+/// start-of-message marker and a few more characters.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let letters = rng.range(2..=8);
+    let mut datastream = (0..size.saturating_sub(20))
+        .map(|_| char::from(b'a' + rng.below(letters) as u8))
+        .collect::<String>();
+    let mut marker = ('a'..='z').collect_vec();
+    rng.shuffle(&mut marker);
+    datastream.extend(&marker[..START_OF_MESSAGE_HEADER_SIZE]);
+    datastream.push_str(&rng.word(1..=5));
+    datastream.push('\n');
+    datastream
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = &'a str;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_datastream(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> PartResult {
+        let message = ElfMessageParser { data: input };
+        Ok(message.header()?.len().into())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> PartResult {
+        let message = ElfMessageParser { data: input };
+        Ok(message.start_of_message_header()?.len().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{self, agree};
+
+    /// The example datastreams, with the end of their header and start of
+    /// message header.
+    const EXAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
+    #[test]
+    fn part1_examples() {
+        for (data, header_end, _) in EXAMPLES {
+            let message = ElfMessageParser { data };
+            assert_eq!(message.header().unwrap().len(), header_end, "{data}");
+        }
+    }
+
+    #[test]
+    fn part2_examples() {
+        for (data, _, start_of_message_end) in EXAMPLES {
+            let message = ElfMessageParser { data };
+            assert_eq!(
+                message.start_of_message_header().unwrap().len(),
+                start_of_message_end,
+                "{data}"
+            );
+        }
+    }
+
+    #[test]
+    fn reject_other_characters() {
+        assert_eq!(
+            parse_datastream("abcD\n").unwrap_err(),
+            ParseError::new(1, 4, "D", "a lowercase letter")
+        );
+        assert_eq!(
+            parse_datastream("abcd\nefgh\n").unwrap_err(),
+            ParseError::new(2, 1, "efgh", "the end of the input")
+        );
+    }
+
+    /// The end of the first window of `size` unique characters, found by
+    /// checking every window on its own.
+    fn naive_marker_end(data: &[char], size: usize) -> Option<usize> {
+        (size..=data.len()).find(|&end| data[end - size..end].iter().all_unique())
+    }
+
+    #[test]
+    fn markers_match_rescanning() {
+        property::check(
+            |rng| {
+                let letters = rng.range(1..=16);
+                (0..rng.range(0..=40))
+                    .map(|_| char::from(b'a' + rng.below(letters) as u8))
+                    .collect::<Vec<_>>()
+            },
+            |data| {
+                let string = data.iter().collect::<String>();
+                let message = ElfMessageParser { data: &string };
+                agree(
+                    (
+                        naive_marker_end(data, HEADER_SIZE),
+                        naive_marker_end(data, START_OF_MESSAGE_HEADER_SIZE),
+                    ),
+                    (
+                        message.header().ok().map(|header| header.len()),
+                        message
+                            .start_of_message_header()
+                            .ok()
+                            .map(|header| header.len()),
+                    ),
+                )
+            },
+        );
+    }
+}
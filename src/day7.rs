@@ -0,0 +1,594 @@
+use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::{Rc, Weak};
+
+use itertools::Itertools;
+
+use crate::{
+    generate::Rng,
+    parsing::lines,
+    solution::{PartResult, Solution},
+    visualize::Visualize,
+    ParseError,
+};
+
+#[derive(Debug, Clone)]
+pub struct Command<'a> {
+    /// The line of the input the command is on.
+    pub line: usize,
+    pub arguments: Vec<&'a str>,
+    /// The lines after the command up to the next one, as they are in the
+    /// input.
+    pub output: &'a str,
+}
+
+impl Command<'_> {
+    /// The command as it was typed.
+    fn text(&self) -> String {
+        format!("$ {}", self.arguments.join(" "))
+    }
+}
+
+#[derive(Debug)]
+pub struct ShellExecution<'a> {
+    pub commands: Vec<Command<'a>>,
+}
+
+#[derive(Debug)]
+pub enum LsOutputItem<'a> {
+    File(&'a str, usize),
+    Directory(&'a str),
+}
+
+#[derive(Debug)]
+pub struct LsOutput<'a>(pub Vec<LsOutputItem<'a>>);
+
+impl<'a> TryFrom<&'a str> for LsOutput<'a> {
+    type Error = ParseError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let mut entries = Vec::new();
+        for line in lines(value) {
+            if line.text.is_empty() {
+                continue;
+            }
+            let mut words = line.words();
+            let kind = words.word("dir or the size of a file")?;
+            let name = words.word("a name")?;
+            if kind == "dir" {
+                // A directory entry.
+                entries.push(LsOutputItem::Directory(name));
+            } else if let Ok(number) = kind.parse::<usize>() {
+                // A file entry.
+                entries.push(LsOutputItem::File(name, number));
+            } else {
+                return Err(line.error(kind, "dir or the size of a file"));
+            }
+            words.end()?;
+        }
+        Ok(LsOutput(entries))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ShellExecution<'a> {
+    type Error = ParseError;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let mut result = ShellExecution {
+            commands: Vec::new(),
+        };
+        // Where the output of the last command starts in the input, once the
+        // line after it is read.
+        let mut output_start = None;
+        for line in lines(input) {
+            // The lines are slices of the input, so this is where it starts.
+            let start = line.text.as_ptr() as usize - input.as_ptr() as usize;
+            let command_output_start = *output_start.get_or_insert(start);
+            let mut parts = line.text.split(' ').filter(|part| !part.is_empty());
+            match parts.next() {
+                Some("$") => {
+                    // A command.
+                    let arguments = parts.collect::<Vec<_>>();
+                    if arguments.is_empty() {
+                        return Err(line.error(line.text, "a command after $"));
+                    }
+                    if let Some(command) = result.commands.last_mut() {
+                        command.output = &input[command_output_start..start];
+                    }
+                    result.commands.push(Command {
+                        line: line.number,
+                        arguments,
+                        output: "",
+                    });
+                    output_start = None;
+                }
+                // A command's output, empty lines included, so the lines of
+                // the output still line up with the input.
+                Some(_) if result.commands.is_empty() => {
+                    return Err(line.error(line.text, "a command"));
+                }
+                _ => {}
+            }
+        }
+        if let (Some(command), Some(output_start)) = (result.commands.last_mut(), output_start) {
+            command.output = &input[output_start..];
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Debug)]
+pub enum DirectoryEntry<'a> {
+    File(&'a str, usize),
+    Directory(Rc<RefCell<Directory<'a>>>),
+}
+
+impl<'a> DirectoryEntry<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            DirectoryEntry::File(name, _) => name,
+            DirectoryEntry::Directory(directory) => RefCell::borrow(directory).name,
+        }
+    }
+}
+
+impl Display for DirectoryEntry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DirectoryEntry::File(name, size) => {
+                write!(f, "- {} (file, size={})", name, size).unwrap()
+            }
+            DirectoryEntry::Directory(directory) => {
+                write!(f, "{}", RefCell::borrow(directory)).unwrap()
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Directory<'a> {
+    pub parent: Weak<RefCell<Directory<'a>>>,
+    pub name: &'a str,
+    pub content: HashMap<&'a str, DirectoryEntry<'a>>,
+}
+
+impl<'a> Directory<'a> {
+    pub fn add_entry(&mut self, entry: DirectoryEntry<'a>) {
+        self.content.insert(entry.name(), entry);
+    }
+
+    /// The size of everything in this directory, which stops at `usize::MAX`
+    /// rather than overflowing. That's far more than fits on the disk anyway.
+    pub fn total_size(&self) -> usize {
+        let mut total_size: usize = 0;
+        for entry in self.content.values() {
+            match entry {
+                DirectoryEntry::File(_, size) => {
+                    total_size = total_size.saturating_add(*size);
+                }
+                DirectoryEntry::Directory(directory) => {
+                    total_size = total_size.saturating_add(RefCell::borrow(directory).total_size());
+                }
+            }
+        }
+        total_size
+    }
+}
+
+impl Display for Directory<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "- {} (dir)", self.name)?;
+        for entry in self.content.values().sorted_by_key(|entry| entry.name()) {
+            // Indent the entries of subdirectories further.
+            write!(f, "\n\t{}", entry.to_string().replace('\n', "\n\t"))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn total_filesize_smaller_than(directory: Rc<RefCell<Directory>>, max_size: usize) -> usize {
+    directory_sizes(&directory)
+        .into_iter()
+        .map(|(size, _)| size)
+        .filter(|size| *size <= max_size)
+        .fold(0, usize::saturating_add)
+}
+
+/// The sizes of this directory and every directory below it, each added up
+/// once from the sizes of the directories inside it.
+pub fn directory_sizes<'a>(
+    directory: &Rc<RefCell<Directory<'a>>>,
+) -> Vec<(usize, Rc<RefCell<Directory<'a>>>)> {
+    fn add_sizes<'a>(
+        directory: &Rc<RefCell<Directory<'a>>>,
+        sizes: &mut Vec<(usize, Rc<RefCell<Directory<'a>>>)>,
+    ) -> usize {
+        let mut total_size: usize = 0;
+        for entry in RefCell::borrow(directory).content.values() {
+            let size = match entry {
+                DirectoryEntry::File(_, size) => *size,
+                DirectoryEntry::Directory(directory) => add_sizes(directory, sizes),
+            };
+            total_size = total_size.saturating_add(size);
+        }
+        sizes.push((total_size, Rc::clone(directory)));
+        total_size
+    }
+
+    let mut sizes = Vec::new();
+    add_sizes(directory, &mut sizes);
+    sizes
+}
+
+/// The smallest directory that is at least `minimum_to_free` big, this one or
+/// one below it, if any.
+pub fn smallest_to_delete<'a>(
+    directory: Rc<RefCell<Directory<'a>>>,
+    minimum_to_free: usize,
+) -> Option<Rc<RefCell<Directory<'a>>>> {
+    directory_sizes(&directory)
+        .into_iter()
+        .filter(|(size, _)| *size >= minimum_to_free)
+        .min_by_key(|(size, _)| *size)
+        .map(|(_, directory)| directory)
+}
+
+/// Build the filesystem that was explored in the shell execution, returning
+/// the root directory.
+pub fn build_filesystem<'a>(
+    shell_execution: &ShellExecution<'a>,
+) -> Result<Rc<RefCell<Directory<'a>>>, ParseError> {
+    let root_directory = Rc::new_cyclic(|weak| {
+        RefCell::new(Directory {
+            parent: weak.clone(),
+            name: "/",
+            content: HashMap::new(),
+        })
+    });
+
+    let mut current_directory = Rc::clone(&root_directory);
+    // We assume that there is always an ls before a cd, so the filesystem has
+    // all the necessary info to cd.
+    for command in shell_execution.commands.iter() {
+        let command_error =
+            |expected: &str| ParseError::new(command.line, 1, command.text(), expected);
+        match command.arguments[..] {
+            ["ls"] => {
+                // Add all the entries to the current directory.
+                let ls_entries = LsOutput::try_from(command.output)
+                    .map_err(|error| error.offset(command.line, 0))?;
+                for entry in ls_entries.0 {
+                    match entry {
+                        LsOutputItem::File(name, size) => {
+                            // Add the file to the current directory.
+                            current_directory
+                                .borrow_mut()
+                                .add_entry(DirectoryEntry::File(name, size));
+                        }
+                        LsOutputItem::Directory(name) => {
+                            // Add the directory to the current directory.
+                            current_directory
+                                .borrow_mut()
+                                .add_entry(DirectoryEntry::Directory(Rc::new(RefCell::new(
+                                    Directory {
+                                        parent: Rc::downgrade(&Rc::clone(&current_directory)),
+                                        name,
+                                        content: HashMap::new(),
+                                    },
+                                ))))
+                        }
+                    }
+                }
+            }
+            ["cd", target] => {
+                if !command.output.trim().is_empty() {
+                    return Err(command_error("cd without any output"));
+                }
+                // Change `current_directory`.
+                if target == ".." {
+                    let current_directory_borrowed = RefCell::borrow(&current_directory);
+                    let new_directory = Rc::clone(
+                        &current_directory_borrowed
+                            .parent
+                            .upgrade()
+                            .ok_or_else(|| command_error("a directory with a parent"))?,
+                    );
+                    drop(current_directory_borrowed);
+                    current_directory = new_directory;
+                } else if target == "/" {
+                    current_directory = Rc::clone(&root_directory);
+                } else {
+                    let new_directory;
+                    let current_directory_borrowed: Ref<_> = RefCell::borrow(&current_directory);
+                    if let Some(DirectoryEntry::Directory(directory)) =
+                        current_directory_borrowed.content.get(target)
+                    {
+                        new_directory = Rc::clone(directory);
+                    } else {
+                        return Err(command_error("cd into a directory listed by ls"));
+                    }
+                    drop(current_directory_borrowed);
+                    current_directory = new_directory;
+                }
+            }
+            _ => {
+                return Err(command_error("ls or cd <directory>"));
+            }
+        }
+    }
+
+    Ok(root_directory)
+}
+
+/// The size of the smallest directory that frees up enough space to run the
+/// update when deleted.
+pub fn smallest_to_delete_size(
+    root_directory: Rc<RefCell<Directory>>,
+) -> Result<usize, &'static str> {
+    let free = 70_000_000usize
+        .checked_sub(RefCell::borrow(&root_directory).total_size())
+        .ok_or("The files don't fit on the disk.")?;
+    let needed = match 30_000_000usize.checked_sub(free) {
+        Some(needed) if needed > 0 => needed,
+        _ => return Err("There is already enough free space for the update."),
+    };
+    smallest_to_delete(root_directory, needed)
+        .map(|directory| RefCell::borrow(&directory).total_size())
+        .ok_or("No directory is big enough to delete.")
+}
+
+/// Generate a transcript that explores a filesystem with about `size`
+/// entries. The files always add up to between 40,000,000 and 70,000,000, with
+/// a directory big enough to free up the space for the update.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    enum Entry {
+        File(String, u64),
+        Directory(String, usize),
+    }
+    fn entry_name(entry: &Entry) -> &str {
+        match entry {
+            Entry::File(name, _) | Entry::Directory(name, _) => name,
+        }
+    }
+
+    // The entries of every directory, with the root first and `a` second.
+    // Files start out with a weight, which is scaled to a size at the end.
+    // Keeping the weights small keeps the total small enough to scale.
+    let maximum_weight = (10_000_000 / size.max(1) as u64).clamp(1, 1_000);
+    let mut directories = vec![Vec::new(), vec![Entry::File("a".to_string(), 1)]];
+    directories[0].push(Entry::Directory("a".to_string(), 1));
+    for _ in 2..size {
+        let parent = rng.index(directories.len());
+        let name = loop {
+            let mut name = rng.word(1..=8);
+            if rng.chance(1, 2) {
+                name = format!("{name}.{}", rng.word(1..=3));
+            }
+            if directories[parent]
+                .iter()
+                .all(|entry| entry_name(entry) != name)
+            {
+                break name;
+            }
+        };
+        let entry = if rng.chance(1, 4) {
+            directories.push(Vec::new());
+            Entry::Directory(name, directories.len() - 1)
+        } else {
+            Entry::File(name, rng.range(1..=maximum_weight))
+        };
+        directories[parent].push(entry);
+    }
+
+    // Directories are always added after their parent, so going backwards
+    // every subdirectory is done before the directory it's in.
+    let mut weights = vec![0; directories.len()];
+    for directory in (0..directories.len()).rev() {
+        weights[directory] = directories[directory]
+            .iter()
+            .map(|entry| match entry {
+                Entry::File(_, weight) => *weight,
+                Entry::Directory(_, directory) => weights[*directory],
+            })
+            .sum();
+    }
+    // Make `a` at least as heavy as everything else together, so it can
+    // always be deleted.
+    let rest = weights[0] - weights[1];
+    if let Some(Entry::File(_, weight)) = directories[1].first_mut() {
+        *weight += rest.saturating_sub(weights[1]);
+    }
+    let total = weights[0] + rest.saturating_sub(weights[1]);
+    // Scale the weights so the total is between the bounds, and the space
+    // that has to be freed, the total minus 40,000,000, fits in `a`.
+    let minimum = 40_000_000u64.div_ceil(total);
+    let maximum = (70_000_000 / total).min(40_000_000u64.checked_div(rest).unwrap_or(u64::MAX));
+    let scale = rng.range(minimum..=maximum);
+
+    fn explore(directories: &[Vec<Entry>], directory: usize, scale: u64, transcript: &mut String) {
+        transcript.push_str("$ ls\n");
+        for entry in &directories[directory] {
+            match entry {
+                Entry::File(name, weight) => {
+                    transcript.push_str(&format!("{} {name}\n", weight * scale))
+                }
+                Entry::Directory(name, _) => transcript.push_str(&format!("dir {name}\n")),
+            }
+        }
+        for entry in &directories[directory] {
+            if let Entry::Directory(name, subdirectory) = entry {
+                transcript.push_str(&format!("$ cd {name}\n"));
+                explore(directories, *subdirectory, scale, transcript);
+                transcript.push_str("$ cd ..\n");
+            }
+        }
+    }
+    let mut transcript = "$ cd /\n".to_string();
+    explore(&directories, 0, scale, &mut transcript);
+    transcript
+}
+
+/// The filesystem being explored, a command at a time.
+pub struct ExplorationVisualization<'a> {
+    execution: ShellExecution<'a>,
+    /// The amount of commands that are done.
+    done: usize,
+}
+
+impl Visualize for ExplorationVisualization<'_> {
+    fn frame(&self) -> String {
+        // Every prefix of the commands builds, as all of them do.
+        let explored = ShellExecution {
+            commands: self.execution.commands[..self.done].to_vec(),
+        };
+        let root_directory =
+            build_filesystem(&explored).expect("The commands were checked when parsing.");
+        let root_directory = RefCell::borrow(&root_directory);
+        let last = match self.done {
+            0 => "Nothing yet.".to_string(),
+            done => self.execution.commands[done - 1].text(),
+        };
+        format!(
+            "{root_directory}\n\nCommand {} of {}, {} in total: {last}",
+            self.done,
+            self.execution.commands.len(),
+            root_directory.total_size()
+        )
+    }
+
+    fn step(&mut self) -> bool {
+        self.done < self.execution.commands.len() && {
+            self.done += 1;
+            true
+        }
+    }
+
+    fn steps(&self) -> usize {
+        self.execution.commands.len()
+    }
+}
+
+/// Show the filesystem filling up as the commands explore it.
+pub fn visualize(input: &str) -> Result<Box<dyn Visualize + '_>, ParseError> {
+    let execution = ShellExecution::try_from(input)?;
+    build_filesystem(&execution)?;
+    Ok(Box::new(ExplorationVisualization { execution, done: 0 }))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = Rc<RefCell<Directory<'a>>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let shell_execution = ShellExecution::try_from(input)?;
+        let root_directory = build_filesystem(&shell_execution)?;
+        log::debug!("Filesystem:\n{}", RefCell::borrow(&root_directory));
+        Ok(root_directory)
+    }
+
+    fn part1(root_directory: &Self::Input<'_>) -> PartResult {
+        Ok(total_filesize_smaller_than(Rc::clone(root_directory), 100000).into())
+    }
+
+    fn part2(root_directory: &Self::Input<'_>) -> PartResult {
+        Ok(smallest_to_delete_size(Rc::clone(root_directory))?.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_given_input() -> Result<(), Box<dyn Error>> {
+        let given = include_str!("../data/day7/example.txt");
+
+        // 2. Parse the utf-8 input into a shell execution history.
+        // Shell execution history:
+        //  - Commands
+        //  - Output
+        let shell_execution = ShellExecution::try_from(given)?;
+
+        let root_directory = build_filesystem(&shell_execution)?;
+
+        assert_eq!(total_filesize_smaller_than(root_directory, 100_000), 95_437);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<(), Box<dyn Error>> {
+        let given = include_str!("../data/day7/example.txt");
+
+        let shell_execution = ShellExecution::try_from(given)?;
+        let root_directory = build_filesystem(&shell_execution)?;
+
+        assert_eq!(smallest_to_delete_size(root_directory), Ok(24_933_642));
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_to_delete() -> Result<(), Box<dyn Error>> {
+        let small = ShellExecution::try_from("$ cd /\n$ ls\n100 a\n")?;
+        assert!(smallest_to_delete_size(build_filesystem(&small)?).is_err());
+        let full = ShellExecution::try_from("$ cd /\n$ ls\n80000000 a\n")?;
+        assert!(smallest_to_delete_size(build_filesystem(&full)?).is_err());
+        let huge = format!("$ cd /\n$ ls\n{0} a\n{0} b\n", usize::MAX);
+        let huge = build_filesystem(&ShellExecution::try_from(huge.as_str())?)?;
+        assert_eq!(RefCell::borrow(&huge).total_size(), usize::MAX);
+        assert!(smallest_to_delete_size(huge).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_bad_listings() -> Result<(), Box<dyn Error>> {
+        let extra = ShellExecution::try_from("$ cd /\n$ ls\ndir a\n12 b c\n")?;
+        assert_eq!(
+            build_filesystem(&extra).unwrap_err(),
+            ParseError::new(4, 6, "c", "the end of the line")
+        );
+        let kind = ShellExecution::try_from("$ cd /\n$ ls\nfile b\n")?;
+        assert_eq!(
+            build_filesystem(&kind).unwrap_err(),
+            ParseError::new(3, 1, "file", "dir or the size of a file")
+        );
+        assert_eq!(
+            ShellExecution::try_from("$ cd /\n$\n").unwrap_err(),
+            ParseError::new(2, 1, "$", "a command after $")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete_root() -> Result<(), Box<dyn Error>> {
+        let flat =
+            ShellExecution::try_from("$ cd /\n$ ls\n50000000 a\ndir b\n$ cd b\n$ ls\n1 c\n")?;
+        assert_eq!(
+            smallest_to_delete_size(build_filesystem(&flat)?),
+            Ok(50_000_001)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn visualize_exploration() -> Result<(), Box<dyn Error>> {
+        let given = include_str!("../data/day7/example.txt");
+
+        let mut visualization = visualize(given)?;
+        assert!(visualization
+            .frame()
+            .starts_with("- / (dir)\n\nCommand 0 of 10"));
+        visualization.step();
+        visualization.step();
+        assert_eq!(
+            visualization.frame(),
+            "- / (dir)\n\t- a (dir)\n\t- b.txt (file, size=14848514)\n\t- c.dat (file, size=8504156)\n\t- d (dir)\n\nCommand 2 of 10, 23352670 in total: $ ls"
+        );
+        while visualization.step() {}
+        assert!(visualization.frame().ends_with("48381165 in total: $ ls"));
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{
+    generate::Rng,
+    geometry::{Direction, Point},
+    grid::Grid,
+    image::{self, Color, Image},
+    parsing::grid_of_digits,
+    solution::{PartResult, Solution},
+    visualize::Visualize,
+    ParseError,
+};
+
+pub type Tree = u8;
+
+#[derive(Debug)]
+pub struct Trees(pub Grid<Tree>);
+
+impl Trees {
+    pub fn is_tree_visible(&self, row: usize, column: usize) -> bool {
+        let height = self.0[Point::new(column, row)];
+        Direction::ALL.into_iter().any(|direction| {
+            self.0
+                .ray(Point::new(column, row), direction.unit())
+                .all(|position| self.0[position] < height)
+        })
+    }
+
+    /// Whether every tree can be seen from outside the grid.
+    pub fn visibility(&self) -> Grid<bool> {
+        // Instead of looking from every tree to the edges, look from the
+        // edges along every row and column, where only trees taller than all
+        // the ones before them can be seen.
+        let mut visible = Grid::new(self.0.width(), self.0.height(), false);
+        let edges = (0..self.0.height())
+            .flat_map(|y| {
+                [
+                    (Point::new(0, y), Direction::Right),
+                    (Point::new(self.0.width() - 1, y), Direction::Left),
+                ]
+            })
+            .chain((0..self.0.width()).flat_map(|x| {
+                [
+                    (Point::new(x, 0), Direction::Down),
+                    (Point::new(x, self.0.height() - 1), Direction::Up),
+                ]
+            }));
+        for (edge, direction) in edges {
+            let mut tallest = None;
+            for position in [edge].into_iter().chain(self.0.ray(edge, direction.unit())) {
+                let height = self.0[position];
+                if Some(height) > tallest {
+                    visible[position] = true;
+                    tallest = Some(height);
+                }
+            }
+        }
+        visible
+    }
+
+    pub fn visible_trees(&self) -> usize {
+        self.visibility().iter().filter(|visible| **visible).count()
+    }
+
+    pub fn tree_scenic_score(&self, row: usize, column: usize) -> usize {
+        let own_height = self.0[Point::new(column, row)];
+        Direction::ALL
+            .into_iter()
+            .map(|direction| {
+                // The view ends at the edge or at the first tree that is at
+                // least as tall, which can still be seen.
+                let mut distance = 0;
+                for position in self.0.ray(Point::new(column, row), direction.unit()) {
+                    distance += 1;
+                    if self.0[position] >= own_height {
+                        break;
+                    }
+                }
+                distance
+            })
+            .product()
+    }
+
+    pub fn best_scenic_score(&self) -> usize {
+        self.0
+            .positions()
+            .map(|position| self.tree_scenic_score(position.y, position.x))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// The color of a tree of a height, green when it can be seen from outside
+/// the grid and dark gray when it can't.
+fn visibility_color(height: Tree, visible: bool) -> Color {
+    let height = f64::from(height) / 9.0;
+    if visible {
+        [0, (100.0 + 155.0 * height) as u8, 0]
+    } else {
+        image::gray(0.1 + 0.3 * height)
+    }
+}
+
+impl Trees {
+    /// The heights of the trees, from black for 0 to white for 9.
+    pub fn heightmap(&self) -> Image {
+        Image::from_fn(self.0.width(), self.0.height(), |x, y| {
+            image::gray(f64::from(self.0[Point::new(x, y)]) / 9.0)
+        })
+    }
+
+    /// The trees that can be seen from outside the grid in green, by
+    /// height.
+    pub fn visibility_map(&self) -> Image {
+        let visibility = self.visibility();
+        Image::from_fn(self.0.width(), self.0.height(), |x, y| {
+            visibility_color(self.0[Point::new(x, y)], visibility[Point::new(x, y)])
+        })
+    }
+
+    /// The scenic scores of the trees as a heatmap.
+    pub fn scenic_map(&self) -> Image {
+        let scores = self
+            .0
+            .map(|position, _| self.tree_scenic_score(position.y, position.x) as f64);
+        let best = scores.iter().copied().fold(1.0, f64::max);
+        // Most trees score low next to the best ones, so spread out the low
+        // scores.
+        Image::from_fn(self.0.width(), self.0.height(), |x, y| {
+            image::heat((scores[Point::new(x, y)] / best).sqrt())
+        })
+    }
+}
+
+impl TryFrom<&str> for Trees {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        grid_of_digits(value, "the height of a tree").map(Trees)
+    }
+}
+
+/// Generate a square grid of trees that is `size` trees wide.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| char::from(b'0' + rng.below(10) as u8))
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// The trees that can be seen from outside the grid, found a row at a time.
+pub struct VisibilityVisualization {
+    trees: Trees,
+    visibility: Grid<bool>,
+    /// The amount of rows that are done.
+    done: usize,
+}
+
+impl Visualize for VisibilityVisualization {
+    fn frame(&self) -> String {
+        // Trees that can't be seen are left out of the rows that are done.
+        let trees = self.trees.0.map(|position, height| {
+            if position.y < self.done && !self.visibility[position] {
+                '.'
+            } else {
+                char::from(b'0' + height)
+            }
+        });
+        let visible = self
+            .visibility
+            .rows()
+            .take(self.done)
+            .flatten()
+            .filter(|visible| **visible)
+            .count();
+        format!(
+            "{trees}\n\nRow {} of {}, {visible} trees visible.",
+            self.done,
+            self.trees.0.height()
+        )
+    }
+
+    fn image(&self) -> Option<Image> {
+        Some(Image::from_fn(
+            self.trees.0.width(),
+            self.trees.0.height(),
+            |x, y| {
+                let height = self.trees.0[Point::new(x, y)];
+                if y < self.done {
+                    visibility_color(height, self.visibility[Point::new(x, y)])
+                } else {
+                    image::gray(f64::from(height) / 9.0)
+                }
+            },
+        ))
+    }
+
+    fn step(&mut self) -> bool {
+        self.done < self.trees.0.height() && {
+            self.done += 1;
+            true
+        }
+    }
+
+    fn steps(&self) -> usize {
+        self.trees.0.height()
+    }
+}
+
+/// Show which trees can be seen, a row at a time.
+pub fn visualize(input: &str) -> Result<Box<dyn Visualize>, ParseError> {
+    let trees = Trees::try_from(input)?;
+    Ok(Box::new(VisibilityVisualization {
+        visibility: trees.visibility(),
+        trees,
+        done: 0,
+    }))
+}
+
+/// Draw the heights, the visible trees and the scenic scores.
+pub fn draw(input: &str) -> Result<Vec<(&'static str, Image)>, ParseError> {
+    let trees = Trees::try_from(input)?;
+    Ok(vec![
+        ("heights", trees.heightmap()),
+        ("visibility", trees.visibility_map()),
+        ("scenic", trees.scenic_map()),
+    ])
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input<'a> = Trees;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Trees::try_from(input)
+    }
+
+    fn part1(trees: &Self::Input<'_>) -> PartResult {
+        Ok(trees.visible_trees().into())
+    }
+
+    fn part2(trees: &Self::Input<'_>) -> PartResult {
+        Ok(trees.best_scenic_score().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{self, agree};
+
+    const EXAMPLE: &str = include_str!("../data/day8/example.txt");
+
+    #[test]
+    fn part1_example() {
+        let trees = Trees::try_from(EXAMPLE).unwrap();
+        assert_eq!(trees.visible_trees(), 21);
+    }
+
+    #[test]
+    fn visualize_and_draw_trees() {
+        let trees = Trees::try_from(EXAMPLE).unwrap();
+        let mut visualization = visualize(EXAMPLE).unwrap();
+        while visualization.step() {}
+        assert_eq!(
+            visualization.frame(),
+            "30373\n255.2\n65.32\n3.5.9\n35390\n\nRow 5 of 5, 21 trees visible."
+        );
+        assert_eq!(visualization.image(), Some(trees.visibility_map()));
+
+        let images = draw(EXAMPLE).unwrap();
+        let scenic = &images[2].1;
+        // The best tree is the brightest.
+        assert_eq!(scenic.get(2, 3), image::WHITE);
+        assert_eq!(images[0].1.get(4, 3), image::WHITE);
+    }
+
+    #[test]
+    fn part2_example() {
+        let trees = Trees::try_from(EXAMPLE).unwrap();
+        assert_eq!(trees.tree_scenic_score(3, 2), 8);
+        assert_eq!(trees.best_scenic_score(), 8);
+    }
+
+    #[test]
+    fn visible_trees_match_scanning() {
+        property::check(
+            |rng| {
+                let (rows, columns) = (rng.range_usize(1..=8), rng.range_usize(1..=8));
+                let highest = rng.range(0..=9);
+                (0..rows)
+                    .map(|_| {
+                        (0..columns)
+                            .map(|_| rng.range(0..=highest) as u8)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |grid| {
+                // Shrinking rows on their own can make the grid ragged.
+                if grid.first().is_none_or(Vec::is_empty)
+                    || grid.iter().any(|row| row.len() != grid[0].len())
+                {
+                    return Ok(());
+                }
+                let trees = Trees(Grid::from_fn(grid[0].len(), grid.len(), |x, y| grid[y][x]));
+                let naive = trees
+                    .0
+                    .map(|position, _| trees.is_tree_visible(position.y, position.x));
+                agree(naive, trees.visibility())
+            },
+        );
+    }
+}
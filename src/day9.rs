@@ -0,0 +1,534 @@
+use std::collections::HashSet;
+
+use crate::{
+    generate::Rng,
+    geometry::{Bounds, Direction, Point},
+    image::{self, Image},
+    parsing::{keyword_then_int, lines, Line},
+    solution::{PartResult, Solution, Streaming},
+    visualize::Visualize,
+    ParseError, Part,
+};
+
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub direction: Direction,
+    pub amount: usize,
+}
+
+pub fn parse_move(line: &Line) -> Result<Move, ParseError> {
+    let (direction, amount) = keyword_then_int(line, &["U", "D", "L", "R"], "the amount of steps")?;
+    let direction = match direction {
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        _ => Direction::Right,
+    };
+    Ok(Move { direction, amount })
+}
+
+/// The amount of steps at the end of the line of a move.
+fn amount<'a>(line: &Line<'a>) -> &'a str {
+    line.text.rsplit(' ').next().unwrap_or(line.text)
+}
+
+/// The head after the move on the line, which has to stay a 32-bit position.
+fn move_head(head: Point<i32>, change: &Move, line: &Line) -> Result<Point<i32>, ParseError> {
+    let unit = change.direction.unit::<i32>();
+    let moved = |position: i32, unit: i32| {
+        i64::try_from(change.amount)
+            .ok()?
+            .checked_mul(unit.into())?
+            .checked_add(position.into())
+            .and_then(|position| i32::try_from(position).ok())
+    };
+    moved(head.x, unit.x)
+        .zip(moved(head.y, unit.y))
+        .map(|(x, y)| Point::new(x, y))
+        .ok_or_else(|| {
+            line.error(
+                amount(line),
+                format!(
+                    "an amount that keeps the head between {} and {}",
+                    i32::MIN,
+                    i32::MAX
+                ),
+            )
+        })
+}
+
+pub fn parse_moves(input: &str) -> Result<Vec<Move>, ParseError> {
+    let mut head = Point::origin();
+    lines(input)
+        .map(|line| {
+            let change = parse_move(&line)?;
+            head = move_head(head, &change, &line)?;
+            Ok(change)
+        })
+        .collect()
+}
+
+pub struct RopeSimulation<'a> {
+    pub visited_positions: HashSet<Point<i32>>,
+    pub moves: &'a [Move],
+    pub head_coordinate: Point<i32>,
+    pub tail_coordinate: Point<i32>,
+}
+
+impl<'a> From<&'a [Move]> for RopeSimulation<'a> {
+    fn from(moves: &'a [Move]) -> Self {
+        let mut result = Self {
+            visited_positions: HashSet::new(),
+            moves,
+            head_coordinate: Point::origin(),
+            tail_coordinate: Point::origin(),
+        };
+        // The tail starts out on a position as well, even if it never moves.
+        result.visited_positions.insert(result.tail_coordinate);
+
+        for change in result.moves {
+            for _ in 0..change.amount {
+                // A tail that no longer touches the head takes the place the
+                // head just left.
+                let previous = result.head_coordinate;
+                result.head_coordinate += change.direction.unit();
+                if !result.head_coordinate.touches(result.tail_coordinate) {
+                    result.tail_coordinate = previous;
+                    result.visited_positions.insert(result.tail_coordinate);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+pub struct Rope<const S: usize> {
+    pub segments: [Point<i32>; S],
+}
+
+impl<const S: usize> Rope<S> {
+    pub fn attach_to(&mut self, this: usize, other: usize) {
+        let other = self.segments[other];
+        let this = &mut self.segments[this];
+        if !this.touches(other) {
+            *this = this.step_toward(other);
+        }
+    }
+
+    pub fn apply_move(&mut self, movement: Direction) {
+        self.segments[0] += movement.unit();
+        for i in 1..self.segments.len() {
+            log::trace!("attach {i} to {}", i - 1);
+            self.attach_to(i, i - 1);
+        }
+    }
+}
+
+impl<const S: usize> From<[Point<i32>; S]> for Rope<S> {
+    fn from(segments: [Point<i32>; S]) -> Self {
+        Self { segments }
+    }
+}
+
+/// All the positions visited by the tail of a rope with `S` knots while
+/// applying the moves to its head.
+pub fn tail_positions<const S: usize>(moves: &[Move]) -> HashSet<Point<i32>> {
+    let mut rope = Rope::from([Point::origin(); S]);
+    let mut visited = HashSet::from([Point::origin()]);
+
+    for direction in steps(moves) {
+        rope.apply_move(direction);
+        visited.insert(rope.segments[S - 1]);
+    }
+
+    visited
+}
+
+/// Ropes of both lengths following the moves as they are read, with the
+/// positions their tails visited.
+pub struct Ropes {
+    short: Rope<2>,
+    long: Rope<10>,
+    short_visited: HashSet<Point<i32>>,
+    long_visited: HashSet<Point<i32>>,
+}
+
+impl Default for Ropes {
+    fn default() -> Self {
+        Self {
+            short: Rope::from([Point::origin(); 2]),
+            long: Rope::from([Point::origin(); 10]),
+            short_visited: HashSet::from([Point::origin()]),
+            long_visited: HashSet::from([Point::origin()]),
+        }
+    }
+}
+
+/// The most columns and rows of the rope that are shown, around its head.
+const VIEWPORT: Point<i32> = Point::new(80, 30);
+
+/// A rope with ten knots following its head, a step at a time.
+pub struct RopeVisualization {
+    rope: Rope<10>,
+    moves: Vec<Move>,
+    /// The move the head is making and how many of its steps are done.
+    current: (usize, usize),
+    /// The amount of steps that are done, and of all of them.
+    done: usize,
+    total: usize,
+    visited: HashSet<Point<i32>>,
+    /// The positions the head reaches, which the other knots never go
+    /// beyond.
+    bounds: Bounds<i32>,
+}
+
+/// The positions the head reached after every move. Moves are straight, so
+/// only where they end matters.
+fn head_bounds(moves: &[Move]) -> impl Iterator<Item = Bounds<i32>> + '_ {
+    moves.iter().scan(
+        (Point::origin(), Bounds::new(Point::origin())),
+        |(head, bounds), change| {
+            // Parsing made sure the head stays a 32-bit position.
+            *head += change.direction.unit() * change.amount as i32;
+            bounds.include(*head);
+            Some(*bounds)
+        },
+    )
+}
+
+/// The most pixels a side of a picture of the rope can have, since a rope
+/// that goes far would need more memory to draw than there is.
+const MAX_IMAGE_SIDE: i64 = 4096;
+
+/// Whether a picture of the bounds is small enough to draw.
+fn fits_in_image(bounds: &Bounds<i32>) -> bool {
+    let side = |min: i32, max: i32| i64::from(max) - i64::from(min) + 1;
+    side(bounds.min.x, bounds.max.x) <= MAX_IMAGE_SIDE
+        && side(bounds.min.y, bounds.max.y) <= MAX_IMAGE_SIDE
+}
+
+/// The positions the tail visited in white, with the start in green and
+/// the knots of the rope, if any, in red for the head and orange for the
+/// others.
+pub fn visited_map(
+    visited: &HashSet<Point<i32>>,
+    knots: &[Point<i32>],
+    bounds: Bounds<i32>,
+) -> Image {
+    Image::from_fn(bounds.width() as usize, bounds.height() as usize, |x, y| {
+        let position = bounds.min + Point::new(x as i32, y as i32);
+        match knots.iter().position(|knot| *knot == position) {
+            Some(0) => [255, 0, 0],
+            Some(_) => [255, 150, 0],
+            None if position == Point::origin() => [0, 200, 0],
+            None if visited.contains(&position) => image::WHITE,
+            None => image::BLACK,
+        }
+    })
+}
+
+impl Visualize for RopeVisualization {
+    fn frame(&self) -> String {
+        let head = self.rope.segments[0];
+        // Show everything, unless it doesn't fit, then only what is around
+        // the head.
+        let shown = |coordinate: fn(Point<i32>) -> i32, size: i32| {
+            let (start, end) = (coordinate(self.bounds.min), coordinate(self.bounds.max));
+            if end - start < size {
+                start..=end
+            } else {
+                let start = coordinate(head) - size / 2;
+                start..=start + size - 1
+            }
+        };
+        let mut frame = String::new();
+        for y in shown(|position| position.y, VIEWPORT.y) {
+            for x in shown(|position| position.x, VIEWPORT.x) {
+                let position = Point::new(x, y);
+                frame.push(
+                    match self.rope.segments.iter().position(|knot| *knot == position) {
+                        Some(0) => 'H',
+                        Some(knot) => char::from(b'0' + knot as u8),
+                        None if position == Point::origin() => 's',
+                        None if self.visited.contains(&position) => '#',
+                        None => '.',
+                    },
+                );
+            }
+            frame.push('\n');
+        }
+        frame
+            + &format!(
+                "\nStep {} of {}, the tail visited {} positions.",
+                self.done,
+                self.total,
+                self.visited.len()
+            )
+    }
+
+    fn image(&self) -> Option<Image> {
+        fits_in_image(&self.bounds)
+            .then(|| visited_map(&self.visited, &self.rope.segments, self.bounds))
+    }
+
+    fn step(&mut self) -> bool {
+        let (index, steps) = &mut self.current;
+        while let Some(change) = self.moves.get(*index) {
+            if *steps < change.amount {
+                *steps += 1;
+                self.rope.apply_move(change.direction);
+                self.visited.insert(self.rope.segments[9]);
+                self.done += 1;
+                return true;
+            }
+            *index += 1;
+            *steps = 0;
+        }
+        false
+    }
+
+    fn steps(&self) -> usize {
+        self.total
+    }
+}
+
+/// Every step of the head of the rope.
+fn steps(moves: &[Move]) -> impl Iterator<Item = Direction> + '_ {
+    moves
+        .iter()
+        .flat_map(|single| std::iter::repeat_n(single.direction, single.amount))
+}
+
+/// Show a rope with ten knots following the moves of its head.
+pub fn visualize(input: &str) -> Result<Box<dyn Visualize>, ParseError> {
+    let moves = parse_moves(input)?;
+    Ok(Box::new(RopeVisualization {
+        rope: Rope::from([Point::origin(); 10]),
+        bounds: head_bounds(&moves)
+            .last()
+            .unwrap_or(Bounds::new(Point::origin())),
+        total: moves.iter().fold(0, |total: usize, single| {
+            total.saturating_add(single.amount)
+        }),
+        moves,
+        current: (0, 0),
+        done: 0,
+        visited: HashSet::from([Point::origin()]),
+    }))
+}
+
+/// Draw the positions visited by the tails of the ropes of both parts.
+pub fn draw(input: &str) -> Result<Vec<(&'static str, Image)>, ParseError> {
+    let moves = parse_moves(input)?;
+    let mut bounds = Bounds::new(Point::origin());
+    for (line, reached) in lines(input).zip(head_bounds(&moves)) {
+        if !fits_in_image(&reached) {
+            return Err(line.error(
+                amount(&line),
+                format!(
+                    "an amount that keeps the rope within {MAX_IMAGE_SIDE} by {MAX_IMAGE_SIDE} \
+                     positions to draw"
+                ),
+            ));
+        }
+        bounds = reached;
+    }
+    Ok(vec![
+        (
+            "visited-2",
+            visited_map(&tail_positions::<2>(&moves), &[], bounds),
+        ),
+        (
+            "visited-10",
+            visited_map(&tail_positions::<10>(&moves), &[], bounds),
+        ),
+    ])
+}
+
+/// Generate `size` moves of the head of the rope.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.choose(&['U', 'D', 'L', 'R']),
+                rng.range(1..=20)
+            )
+        })
+        .collect()
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input<'a> = Vec<Move>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_moves(input)
+    }
+
+    fn part1(moves: &Self::Input<'_>) -> PartResult {
+        let simulation = RopeSimulation::from(moves.as_slice());
+        Ok(simulation.visited_positions.len().into())
+    }
+
+    fn part2(moves: &Self::Input<'_>) -> PartResult {
+        Ok(tail_positions::<10>(moves).len().into())
+    }
+}
+
+impl Streaming for Day9 {
+    type State = Ropes;
+
+    fn read_line(ropes: &mut Self::State, line: Line) -> Result<(), ParseError> {
+        let change = parse_move(&line)?;
+        move_head(ropes.short.segments[0], &change, &line)?;
+        for _ in 0..change.amount {
+            ropes.short.apply_move(change.direction);
+            ropes.long.apply_move(change.direction);
+            ropes.short_visited.insert(ropes.short.segments[1]);
+            ropes.long_visited.insert(ropes.long.segments[9]);
+        }
+        Ok(())
+    }
+
+    fn answer(ropes: &Self::State, part: Part) -> PartResult {
+        match part {
+            Part::One => Ok(ropes.short_visited.len().into()),
+            Part::Two => Ok(ropes.long_visited.len().into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{self, agree, Shrink};
+
+    impl Shrink for Move {
+        fn shrink(&self) -> Vec<Self> {
+            let mut simpler = self
+                .amount
+                .shrink()
+                .into_iter()
+                .map(|amount| Move {
+                    direction: Direction::Up,
+                    amount,
+                })
+                .collect::<Vec<_>>();
+            if self.direction != Direction::Up {
+                simpler.insert(
+                    0,
+                    Move {
+                        direction: Direction::Up,
+                        amount: self.amount,
+                    },
+                );
+            }
+            simpler
+        }
+    }
+
+    fn moves(rng: &mut Rng) -> Vec<Move> {
+        (0..rng.range(0..=12))
+            .map(|_| Move {
+                amount: rng.range_usize(0..=6),
+                direction: Direction::ALL[rng.index(4)],
+            })
+            .collect()
+    }
+
+    /// The positions the tail of a rope with `knots` knots visits, moving
+    /// every knot a step towards the one before it whenever they no longer
+    /// touch.
+    fn naive_tail_positions(moves: &[Move], knots: usize) -> HashSet<Point<i32>> {
+        let mut rope = vec![Point::<i32>::origin(); knots];
+        let mut visited = HashSet::from([Point::origin()]);
+        for single in moves {
+            for _ in 0..single.amount {
+                rope[0] += single.direction.unit();
+                for knot in 1..knots {
+                    let difference = rope[knot - 1] - rope[knot];
+                    if difference.x.abs() > 1 || difference.y.abs() > 1 {
+                        rope[knot] += difference.signum();
+                    }
+                }
+                visited.insert(rope[knots - 1]);
+            }
+        }
+        visited
+    }
+
+    #[test]
+    fn ropes_match_naive_rope() {
+        property::check(moves, |moves| {
+            let naive = naive_tail_positions(moves, 2);
+            agree(
+                naive.clone(),
+                RopeSimulation::from(moves.as_slice()).visited_positions,
+            )?;
+            agree(naive, tail_positions::<2>(moves))?;
+            agree(naive_tail_positions(moves, 10), tail_positions::<10>(moves))
+        });
+    }
+
+    #[test]
+    fn test2() {
+        let input = include_str!("../data/day9/larger-example.txt");
+
+        let moves = parse_moves(input).unwrap();
+
+        let simulation = RopeSimulation::from(moves.as_slice());
+
+        let visited = tail_positions::<10>(&moves);
+
+        println!("total positions: {}", simulation.visited_positions.len());
+        println!("total positions for 10: {}", visited.len());
+        assert_eq!(visited.len(), 36);
+    }
+
+    #[test]
+    fn part1_example() {
+        let moves = parse_moves(include_str!("../data/day9/example.txt")).unwrap();
+
+        let simulation = RopeSimulation::from(moves.as_slice());
+
+        assert_eq!(simulation.visited_positions.len(), 13);
+        assert_eq!(tail_positions::<2>(&moves).len(), 13);
+    }
+
+    #[test]
+    fn visualize_rope() {
+        let mut visualization = visualize(include_str!("../data/day9/example.txt")).unwrap();
+        for _ in 0..4 {
+            visualization.step();
+        }
+        assert_eq!(
+            visualization.frame(),
+            "......\n......\n......\n......\n4321H.\n\nStep 4 of 24, the tail visited 1 positions."
+        );
+        let image = visualization.image().unwrap();
+        assert_eq!((image.width(), image.height()), (6, 5));
+        assert_eq!(image.get(4, 4), [255, 0, 0]);
+    }
+
+    #[test]
+    fn part2_example() {
+        let moves = parse_moves(include_str!("../data/day9/example.txt")).unwrap();
+
+        assert_eq!(tail_positions::<10>(&moves).len(), 1);
+    }
+
+    #[test]
+    fn reject_head_overflow() {
+        assert!(parse_moves("R 2147483647\nL 4294967295\n").is_ok());
+        let error = parse_moves("R 2147483647\nR 1\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert!(parse_moves("U 99999999999999\n").is_err());
+
+        assert!(draw("R 4095\nL 4095\n").is_ok());
+        let error = draw("R 4095\nL 4096\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+}
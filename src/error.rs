@@ -0,0 +1,126 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+};
+
+/// A type that allows easy branching from `main()` while also displaying clean
+/// error messages to the user.
+pub struct ProgramError {
+    error: Box<dyn Display>,
+}
+
+impl Debug for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<T: Display + 'static> From<T> for ProgramError {
+    fn from(error: T) -> Self {
+        Self {
+            error: Box::new(error),
+        }
+    }
+}
+
+/// The reason puzzle input couldn't be parsed, pointing at where in the input
+/// it went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the input, starting at 1.
+    pub line: usize,
+    /// The character in the line, starting at 1.
+    pub column: usize,
+    /// The text that couldn't be parsed.
+    pub text: String,
+    /// A description of what should have been there instead.
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(
+        line: usize,
+        column: usize,
+        text: impl Into<String>,
+        expected: impl Into<String>,
+    ) -> Self {
+        Self {
+            line,
+            column,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error for `part`, which has to be a slice of `line`, so the column
+    /// can be derived from where it is in the line.
+    pub fn in_line(
+        line_number: usize,
+        line: &str,
+        part: &str,
+        expected: impl Into<String>,
+    ) -> Self {
+        let offset = (part.as_ptr() as usize)
+            .saturating_sub(line.as_ptr() as usize)
+            .min(line.len());
+        let column = line
+            .get(..offset)
+            .map_or(offset, |before| before.chars().count())
+            + 1;
+        Self::new(line_number, column, part, expected)
+    }
+
+    /// Move the error further into the input, for errors that were found
+    /// while parsing only a part of it.
+    ///
+    /// The column only moves for errors on the first line, since that is the
+    /// only line that doesn't start at the start of a line.
+    pub fn offset(mut self, lines: usize, columns: usize) -> Self {
+        if self.line == 1 {
+            self.column += columns;
+        }
+        self.line += lines;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found {:?}", self.text)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_from_slice() {
+        let line = "move 1 from x to 3";
+        let error = ParseError::in_line(4, line, &line[12..13], "a stack");
+        assert_eq!(error, ParseError::new(4, 13, "x", "a stack"));
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 13: expected a stack, found \"x\""
+        );
+    }
+
+    #[test]
+    fn offset_only_moves_column_on_first_line() {
+        let error = ParseError::new(1, 1, "W", "X, Y or Z").offset(3, 2);
+        assert_eq!((error.line, error.column), (4, 3));
+        let error = ParseError::new(2, 1, "W", "X, Y or Z").offset(3, 2);
+        assert_eq!((error.line, error.column), (5, 1));
+    }
+}
@@ -0,0 +1,197 @@
+//! Random puzzle inputs, for testing the solvers on inputs bigger and weirder
+//! than the real ones.
+//!
+//! Every day has its own generator next to its parser. The generators only
+//! depend on the seed of the [`Rng`] they're given, so an input that breaks a
+//! solver can always be made again.
+
+use std::ops::RangeInclusive;
+
+/// A small random number generator (SplitMix64) that gives the same numbers
+/// for the same seed on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to, but not including, `bound`, which can't be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "There are no numbers below 0.");
+        // Skip the numbers at the top that would make lower results more
+        // likely than higher ones.
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let number = self.next_u64();
+            if number < zone {
+                return number % bound;
+            }
+        }
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        match (end - start).checked_add(1) {
+            Some(count) => start + self.below(count),
+            None => self.next_u64(),
+        }
+    }
+
+    /// A number in the range, for ranges that fit in a `usize`.
+    pub fn range_usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        self.range(*range.start() as u64..=*range.end() as u64) as usize
+    }
+
+    pub fn index(&mut self, length: usize) -> usize {
+        self.below(length as u64) as usize
+    }
+
+    /// True `numerator` out of `denominator` times.
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.index(index + 1));
+        }
+    }
+
+    /// A lowercase word with a length in the range.
+    pub fn word(&mut self, length: RangeInclusive<usize>) -> String {
+        (0..self.range_usize(length))
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+}
+
+/// The generator of a day.
+pub struct Generator {
+    /// Make an input of roughly the given size. What the size counts differs
+    /// per day, like elves for day 1 or the width of the grid for day 8.
+    pub generate: fn(&mut Rng, usize) -> String,
+    /// The size of the real inputs, used when no size is given.
+    pub real_size: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::dataset_path;
+    use crate::parallel;
+    use crate::solution::SOLUTIONS;
+    use crate::Part;
+    use std::{fs, path::Path};
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..10).map(|_| rng.range(1..=6)).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        assert!(numbers(7).iter().all(|number| (1..=6).contains(number)));
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        for solution in SOLUTIONS {
+            for seed in 0..20 {
+                for size in [1, 2, 5, 30] {
+                    let input = (solution.generator.generate)(&mut Rng::new(seed), size);
+                    let report = solution.run(&input, &Part::ALL);
+                    assert!(
+                        report.is_success(),
+                        "Seed {seed} and size {size}:\n{report}\n{input}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Change the input a little, in the ways that tend to trip up parsers.
+    fn mutate(rng: &mut Rng, input: &str) -> String {
+        let mut chars = input.chars().collect::<Vec<_>>();
+        for _ in 0..rng.range(1..=4) {
+            let position = rng.index(chars.len() + 1);
+            match rng.below(5) {
+                0 => chars.truncate(position),
+                1 => {
+                    let end = rng.range_usize(position..=chars.len());
+                    chars.drain(position..end);
+                }
+                2 => chars.insert(
+                    position,
+                    *rng.choose(&[' ', '\n', '0', '9', 'a', '[', ']', '$', '-', ',', 'é']),
+                ),
+                3 => chars
+                    .splice(position..position, "99999999999999999999".chars())
+                    .for_each(drop),
+                _ => {
+                    let end = rng.range_usize(position..=chars.len());
+                    let copy = chars[position..end].to_vec();
+                    chars.splice(position..position, copy).for_each(drop);
+                }
+            }
+        }
+        chars.into_iter().collect()
+    }
+
+    /// Whether every number in the input has at most six digits. Bigger
+    /// numbers can make valid inputs that ask for more work than a test can
+    /// do, like a rope taking a billion steps.
+    fn small_numbers(input: &str) -> bool {
+        input
+            .split(|char: char| !char.is_ascii_digit())
+            .all(|digits| digits.len() <= 6)
+    }
+
+    #[test]
+    fn mutated_inputs_never_panic() {
+        for solution in SOLUTIONS {
+            let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("data");
+            let mut inputs =
+                vec![
+                    fs::read_to_string(dataset_path(&directory, solution.day, "example")).unwrap(),
+                ];
+            inputs.extend((0..5).map(|seed| (solution.generator.generate)(&mut Rng::new(seed), 5)));
+            for input in &inputs {
+                for seed in 0..200 {
+                    let mutated = mutate(&mut Rng::new(seed), input);
+                    // Both parts run on whatever parses, and have to give
+                    // an answer or an error. The unit tests of the days cover
+                    // the numbers that overflow.
+                    let parts: &[Part] = if small_numbers(&mutated) {
+                        &Part::ALL
+                    } else {
+                        &[]
+                    };
+                    let report = parallel::catch_panic(|| solution.run(&mutated, parts));
+                    assert!(
+                        report.is_ok(),
+                        "Day {} panicked on {mutated:?}: {}",
+                        solution.day,
+                        report.err().unwrap_or_default()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! Points on a plane and the ways to go between them.
+//!
+//! `x` goes right and `y` goes down, like the lines and characters of the
+//! input, so up is towards negative `y`. Points double as the vectors between
+//! them.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Signed numbers, which points can be measured and moved with.
+pub trait Signed:
+    Copy
+    + Ord
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+
+    fn signum(self) -> Self;
+}
+
+macro_rules! signed {
+    ($($type:ty),*) => {
+        $(
+            impl Signed for $type {
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$type>::abs(self)
+                }
+
+                fn signum(self) -> Self {
+                    <$type>::signum(self)
+                }
+            }
+        )*
+    };
+}
+
+signed!(i8, i16, i32, i64, isize);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Signed> Point<T> {
+    /// The point with both coordinates at zero.
+    pub fn origin() -> Self {
+        Self::default()
+    }
+
+    /// The point with the sign of both coordinates, a step of at most one
+    /// in both directions.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// The distance going only up, down, left and right.
+    pub fn manhattan(self, other: Self) -> T {
+        let difference = other - self;
+        difference.x.abs() + difference.y.abs()
+    }
+
+    /// The distance going diagonally as well, so the most of both
+    /// directions.
+    pub fn chebyshev(self, other: Self) -> T {
+        let difference = other - self;
+        difference.x.abs().max(difference.y.abs())
+    }
+
+    /// Whether the points are on top of each other or next to each other,
+    /// diagonals included.
+    pub fn touches(self, other: Self) -> bool {
+        self.chebyshev(other) <= T::ONE
+    }
+
+    /// The point a single step, diagonals included, closer to the other.
+    pub fn step_toward(self, other: Self) -> Self {
+        self + (other - self).signum()
+    }
+
+    /// The vector turned a quarter clockwise around the origin.
+    pub fn rotated_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// The vector turned a quarter counterclockwise around the origin.
+    pub fn rotated_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// The vector made longer by a factor.
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self::Output {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// The four ways to go without going diagonally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The step to go a single cell this way.
+    pub fn unit<T: Signed>(self) -> Point<T> {
+        let (zero, one) = (T::default(), T::ONE);
+        match self {
+            Direction::Up => Point::new(zero, -one),
+            Direction::Right => Point::new(one, zero),
+            Direction::Down => Point::new(zero, one),
+            Direction::Left => Point::new(-one, zero),
+        }
+    }
+
+    /// The direction a quarter clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// The direction a quarter counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// The eight ways to go, diagonals included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    /// The step to go a single cell this way.
+    pub fn unit<T: Signed>(self) -> Point<T> {
+        let (up, right, down, left) = (
+            Direction::Up.unit(),
+            Direction::Right.unit(),
+            Direction::Down.unit(),
+            Direction::Left.unit(),
+        );
+        match self {
+            Direction8::Up => up,
+            Direction8::UpRight => up + right,
+            Direction8::Right => right,
+            Direction8::DownRight => down + right,
+            Direction8::Down => down,
+            Direction8::DownLeft => down + left,
+            Direction8::Left => left,
+            Direction8::UpLeft => up + left,
+        }
+    }
+
+    /// The direction an eighth clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// The direction an eighth counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        Self::ALL[direction as usize * 2]
+    }
+}
+
+/// The smallest rectangle around some points, with both corners in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Signed> Bounds<T> {
+    /// The bounds of just the point.
+    pub fn new(point: Point<T>) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// The bounds of the points, if there are any.
+    pub fn of(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(points.next()?);
+        points.for_each(|point| bounds.include(point));
+        Some(bounds)
+    }
+
+    /// Grow the bounds until the point is in them.
+    pub fn include(&mut self, point: Point<T>) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The amount of columns in the bounds.
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    /// The amount of rows in the bounds.
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_and_move() {
+        let (a, b) = (Point::new(1, -2), Point::new(-3, 4));
+        assert_eq!(a + b, Point::new(-2, 2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(-a * 3, Point::new(-3, 6));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(a.step_toward(b), Point::new(0, -1));
+        assert_eq!(a.step_toward(a), a);
+        assert!(a.touches(Point::new(2, -1)) && !a.touches(Point::new(3, -2)));
+
+        let bounds = Bounds::of([a, b, Point::origin()]).unwrap();
+        assert_eq!(
+            (bounds.min, bounds.max),
+            (Point::new(-3, -2), Point::new(1, 4))
+        );
+        assert_eq!((bounds.width(), bounds.height()), (5, 7));
+        assert!(bounds.contains(Point::new(-3, 4)) && !bounds.contains(Point::new(2, 0)));
+        assert_eq!(Bounds::<i32>::of([]), None);
+    }
+
+    #[test]
+    fn turn() {
+        for direction in Direction::ALL {
+            assert_eq!(
+                direction.turn_right().unit::<i32>(),
+                direction.unit::<i32>().rotated_right()
+            );
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.opposite().unit::<i32>(), -direction.unit::<i32>());
+            assert_eq!(
+                Direction8::from(direction).unit::<i32>(),
+                direction.unit::<i32>()
+            );
+        }
+        assert_eq!(Direction::Up.unit::<i32>(), Point::new(0, -1));
+        assert_eq!(Direction8::DownLeft.unit::<i64>(), Point::new(-1, 1));
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::Up);
+        assert_eq!(Direction8::Up.turn_left().opposite(), Direction8::DownRight);
+    }
+}
@@ -0,0 +1,415 @@
+//! Rectangular grids of cells, as many puzzles come as a picture of
+//! characters.
+//!
+//! Cells are found by their position, with `x` going right and `y` going
+//! down from the top left, like in the input.
+
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{Direction, Direction8, Point},
+    ParseError,
+};
+
+/// The position of a cell.
+pub type Position = Point<usize>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// The rows of cells, top to bottom.
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// A grid with every cell from `cell(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        Self {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| cell(x, y))
+                .collect(),
+        }
+    }
+
+    /// Parse a grid with a character per cell, where every line is a row and
+    /// all of them are as long. `cell` turns a character into a cell, or
+    /// returns `None` if it isn't what was `expected`.
+    pub fn parse(
+        input: &str,
+        cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+        for (index, line) in input.lines().enumerate() {
+            for (column, char) in line.chars().enumerate() {
+                cells.push(
+                    cell(char)
+                        .ok_or_else(|| ParseError::new(index + 1, column + 1, char, expected))?,
+                );
+            }
+            let row_width = cells.len() - height * width.unwrap_or_default();
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(ParseError::new(
+                    index + 1,
+                    1,
+                    line,
+                    format!("a row of {} cells", width.unwrap_or_default()),
+                ));
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(ParseError::new(1, 1, "", "a grid")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    /// The cell at the position, if it is on the grid.
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.y * self.width + position.x])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.contains(position)
+            .then(|| &mut self.cells[position.y * self.width + position.x])
+    }
+
+    /// The positions of all the cells, a row at a time.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// All the cells, a row at a time.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// A grid of the same shape with every cell mapped.
+    pub fn map<U>(&self, mut cell: impl FnMut(Position, &T) -> U) -> Grid<U> {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            let position = Point::new(x, y);
+            cell(position, &self[position])
+        })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "Column {x} isn't on the grid.");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // Chunks can't be empty, and there are no rows to split anyway when
+        // the grid has no columns.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// The position `offset` away from the position, if it is on the grid.
+    pub fn step(&self, position: Position, offset: Point<isize>) -> Option<Position> {
+        let position = Point::new(
+            position.x.checked_add_signed(offset.x)?,
+            position.y.checked_add_signed(offset.y)?,
+        );
+        self.contains(position).then_some(position)
+    }
+
+    /// The cells above, right of, below and left of the position that are
+    /// on the grid.
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(position, direction.unit()))
+    }
+
+    /// The cells around the position that are on the grid, diagonals
+    /// included.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(position, direction.unit()))
+    }
+
+    /// The positions of the cells that can be reached from `start` by
+    /// stepping up, right, down or left onto cells that match, `start` first.
+    /// Empty when `start` doesn't match or isn't on the grid.
+    pub fn region(&self, start: Position, matches: impl Fn(&T) -> bool) -> Vec<Position> {
+        let mut seen = Grid::new(self.width, self.height, false);
+        self.fill(start, &mut seen, |cell| matches(cell))
+    }
+
+    /// The groups of neighbouring cells that are equal, in the order of their
+    /// first cell, a row at a time.
+    pub fn regions(&self) -> Vec<Vec<Position>>
+    where
+        T: PartialEq,
+    {
+        let mut seen = Grid::new(self.width, self.height, false);
+        let mut regions = Vec::new();
+        for position in self.positions() {
+            if !seen[position] {
+                let cell = &self[position];
+                regions.push(self.fill(position, &mut seen, |other| other == cell));
+            }
+        }
+        regions
+    }
+
+    /// Flood fill from `start` over the matching cells that aren't `seen` yet.
+    fn fill(
+        &self,
+        start: Position,
+        seen: &mut Grid<bool>,
+        matches: impl Fn(&T) -> bool,
+    ) -> Vec<Position> {
+        let mut region = Vec::new();
+        if self.get(start).is_some_and(&matches) {
+            seen[start] = true;
+            region.push(start);
+        }
+        let mut next = 0;
+        while let Some(&position) = region.get(next) {
+            next += 1;
+            for neighbour in self.neighbours(position) {
+                if !seen[neighbour] && matches(&self[neighbour]) {
+                    seen[neighbour] = true;
+                    region.push(neighbour);
+                }
+            }
+        }
+        region
+    }
+
+    /// The positions from next to the position to the edge of the grid,
+    /// taking steps of `offset`, like the unit of a direction.
+    pub fn ray(
+        &self,
+        position: Position,
+        offset: Point<isize>,
+    ) -> impl Iterator<Item = Position> + '_ {
+        let mut position = Some(position);
+        std::iter::from_fn(move || {
+            position = self.step(position?, offset);
+            position
+        })
+    }
+
+    /// The grid flipped over the diagonal from the top left, so the rows
+    /// become the columns.
+    pub fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[Point::new(y, x)].clone()
+        })
+    }
+
+    /// The grid turned a quarter clockwise.
+    pub fn rotated_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[Point::new(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    /// The grid turned a quarter counterclockwise.
+    pub fn rotated_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[Point::new(self.width - 1 - y, x)].clone()
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{position:?} isn't on the grid."))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position:?} isn't on the grid."))
+    }
+}
+
+/// The cells like in the input, a line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters() -> Grid<char> {
+        Grid::parse("abc\ndef", Some, "a letter").unwrap()
+    }
+
+    #[test]
+    fn parse_and_iterate() {
+        let grid = letters();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b', 'c'], ['d', 'e', 'f']]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+        assert_eq!(
+            grid.neighbours(Point::new(0, 0)).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 5);
+        assert_eq!(
+            grid.ray(Point::new(2, 1), Direction::Left.unit())
+                .collect::<Vec<_>>(),
+            [Point::new(1, 1), Point::new(0, 1)]
+        );
+        assert_eq!(
+            grid.ray(Point::new(0, 1), Direction8::UpRight.unit())
+                .collect::<Vec<_>>(),
+            [Point::new(1, 0)]
+        );
+        assert_eq!(grid.ray(Point::new(1, 0), Direction::Up.unit()).count(), 0);
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        assert_eq!(
+            Grid::parse("12\n345", |char| char.to_digit(10), "a digit"),
+            Err(ParseError::new(2, 1, "345", "a row of 2 cells"))
+        );
+        assert_eq!(
+            Grid::parse("12\n3x", |char| char.to_digit(10), "a digit"),
+            Err(ParseError::new(2, 2, "x", "a digit"))
+        );
+        assert!(Grid::parse("", Some, "a letter").is_err());
+    }
+
+    #[test]
+    fn turn_and_flip() {
+        let grid = letters();
+        assert_eq!(grid.transposed().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotated_right().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotated_left().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotated_right().rotated_left(), grid);
+        assert_eq!(
+            grid.rotated_right().rotated_right(),
+            grid.map(|position, _| grid[Point::new(2 - position.x, 1 - position.y)])
+        );
+    }
+
+    #[test]
+    fn region() {
+        let grid = Grid::parse(
+            "aab
+bab
+bbb",
+            Some,
+            "a letter",
+        )
+        .unwrap();
+        assert_eq!(
+            grid.region(Point::new(0, 0), |cell| *cell == 'a'),
+            [Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)]
+        );
+        assert_eq!(grid.region(Point::new(2, 0), |cell| *cell == 'b').len(), 6);
+        assert!(grid
+            .region(Point::new(0, 0), |cell| *cell == 'b')
+            .is_empty());
+        assert!(grid.region(Point::new(3, 0), |_| true).is_empty());
+    }
+
+    #[test]
+    fn regions() {
+        let grid = Grid::parse(
+            "aab
+bab
+bba",
+            Some,
+            "a letter",
+        )
+        .unwrap();
+        let regions = grid.regions();
+        assert_eq!(
+            regions
+                .iter()
+                .map(|region| grid[region[0]])
+                .collect::<String>(),
+            "abba"
+        );
+        assert_eq!(
+            regions.iter().map(Vec::len).collect::<Vec<_>>(),
+            [3, 2, 3, 1]
+        );
+        assert_eq!(letters().regions().len(), 6);
+    }
+}
@@ -0,0 +1,488 @@
+//! Pictures of the puzzles: grids of colored pixels, saved as PPM or PNG, and
+//! animated PNGs made of several of them.
+//!
+//! PNGs are compressed with the fixed codes of deflate only, which keeps the
+//! encoder small and still shrinks the big single colored areas of the
+//! puzzles a lot.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::ParseError;
+
+/// The red, green and blue of a pixel.
+pub type Color = [u8; 3];
+
+pub const BLACK: Color = [0, 0, 0];
+pub const WHITE: Color = [255, 255, 255];
+
+/// The scale images are saved at when none is given, as a pixel per cell is
+/// too small to see.
+pub const DEFAULT_SCALE: usize = 4;
+
+/// Makes the named pictures of a day from its input.
+pub type Drawer = fn(&str) -> Result<Vec<(&'static str, Image)>, ParseError>;
+
+/// The shade of gray of a value from 0 to 1.
+pub fn gray(value: f64) -> Color {
+    let shade = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [shade; 3]
+}
+
+/// A color from black through red and yellow to white for a value from 0
+/// to 1, to see the highs and lows of a heatmap.
+pub fn heat(value: f64) -> Color {
+    let value = value.clamp(0.0, 1.0) * 3.0;
+    let channel = |start: f64| ((value - start).clamp(0.0, 1.0) * 255.0).round() as u8;
+    [channel(0.0), channel(1.0), channel(2.0)]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    /// The rows of pixels, top to bottom.
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// An image with the color of every pixel from `color(x, y)`.
+    pub fn from_fn(width: usize, height: usize, color: impl Fn(usize, usize) -> Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| color(x, y))
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// The image with every pixel turned into a square of `factor` pixels.
+    pub fn scaled(&self, factor: usize) -> Image {
+        Image::from_fn(self.width * factor, self.height * factor, |x, y| {
+            self.get(x / factor, y / factor)
+        })
+    }
+
+    /// The image in the top left of one of another size, cut off or with
+    /// the background around it.
+    fn resized(&self, width: usize, height: usize, background: Color) -> Image {
+        Image::from_fn(width, height, |x, y| {
+            if x < self.width && y < self.height {
+                self.get(x, y)
+            } else {
+                background
+            }
+        })
+    }
+
+    /// Write the image as a binary PPM.
+    pub fn write_ppm(&self, output: &mut impl Write) -> io::Result<()> {
+        write!(output, "P6\n{} {}\n255\n", self.width, self.height)?;
+        output.write_all(&self.pixels.concat())
+    }
+
+    /// Write the image as a PNG.
+    pub fn write_png(&self, output: &mut impl Write) -> io::Result<()> {
+        write_header(self.width, self.height, output)?;
+        write_chunk(output, b"IDAT", &zlib(&self.scanlines()))?;
+        write_chunk(output, b"IEND", &[])
+    }
+
+    /// Save the image as a PNG or a PPM, depending on the extension of the
+    /// path.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("png") => self.write_png(&mut file)?,
+            Some("ppm") => self.write_ppm(&mut file)?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "images can only be saved as .png or .ppm",
+                ))
+            }
+        }
+        file.flush()
+    }
+
+    /// The scanlines as PNG wants them, each starting with the filter it
+    /// uses, which is none.
+    fn scanlines(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for row in self.pixels.chunks(self.width) {
+            data.push(0);
+            data.extend(row.concat());
+        }
+        data
+    }
+}
+
+/// An animated PNG being made a frame at a time. The frames are kept
+/// compressed, so long animations still fit in memory.
+#[derive(Debug, Default)]
+pub struct Animation {
+    /// The size of the first frame, which every frame is cut or padded to.
+    size: Option<(usize, usize)>,
+    /// The compressed scanlines of every frame.
+    frames: Vec<Vec<u8>>,
+}
+
+impl Animation {
+    pub fn push(&mut self, frame: &Image) {
+        let (width, height) = *self.size.get_or_insert((frame.width, frame.height));
+        let scanlines = if (frame.width, frame.height) == (width, height) {
+            frame.scanlines()
+        } else {
+            frame.resized(width, height, BLACK).scanlines()
+        };
+        self.frames.push(zlib(&scanlines));
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Write the animation as a PNG that loops forever, showing `fps`
+    /// frames a second.
+    pub fn write(&self, fps: u32, output: &mut impl Write) -> io::Result<()> {
+        let (width, height) = self.size.unwrap_or_default();
+        write_header(width, height, output)?;
+        let count = self.frames.len() as u32;
+        write_chunk(output, b"acTL", &[count.to_be_bytes(), [0; 4]].concat())?;
+        let delay = [
+            1u16.to_be_bytes(),
+            (fps.clamp(1, 1000) as u16).to_be_bytes(),
+        ]
+        .concat();
+        // The frame controls and the data of all but the first frame share
+        // the sequence numbers.
+        let mut sequence = 0u32;
+        for (index, data) in self.frames.iter().enumerate() {
+            let control = [
+                sequence.to_be_bytes().as_slice(),
+                &(width as u32).to_be_bytes(),
+                &(height as u32).to_be_bytes(),
+                // At the top left, replacing the frame before it.
+                &[0; 8],
+                &delay,
+                &[0, 0],
+            ]
+            .concat();
+            write_chunk(output, b"fcTL", &control)?;
+            sequence += 1;
+            if index == 0 {
+                write_chunk(output, b"IDAT", data)?;
+            } else {
+                write_chunk(
+                    output,
+                    b"fdAT",
+                    &[&sequence.to_be_bytes(), data.as_slice()].concat(),
+                )?;
+                sequence += 1;
+            }
+        }
+        write_chunk(output, b"IEND", &[])
+    }
+}
+
+/// The CRC-32 PNG uses for its chunks.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// The checksum at the end of zlib data.
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in bytes {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// Writes bits the way deflate wants them, starting with the lowest bit of
+/// every byte.
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    pending: u32,
+    count: u32,
+}
+
+impl Bits {
+    fn write(&mut self, value: u32, count: u32) {
+        self.pending |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.pending as u8);
+            self.pending >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are written starting with their highest bit.
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.pending as u8);
+        }
+        self.bytes
+    }
+}
+
+/// The shortest lengths of the length codes, and how many extra bits they
+/// have.
+#[rustfmt::skip]
+const LENGTHS: [(u32, u32); 29] = [
+    (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0),
+    (11, 1), (13, 1), (15, 1), (17, 1), (19, 2), (23, 2), (27, 2), (31, 2),
+    (35, 3), (43, 3), (51, 3), (59, 3), (67, 4), (83, 4), (99, 4), (115, 4),
+    (131, 5), (163, 5), (195, 5), (227, 5), (258, 0),
+];
+
+/// The shortest distances of the distance codes, and how many extra bits
+/// they have.
+#[rustfmt::skip]
+const DISTANCES: [(u32, u32); 30] = [
+    (1, 0), (2, 0), (3, 0), (4, 0), (5, 1), (7, 1), (9, 2), (13, 2),
+    (17, 3), (25, 3), (33, 4), (49, 4), (65, 5), (97, 5), (129, 6), (193, 6),
+    (257, 7), (385, 7), (513, 8), (769, 8), (1025, 9), (1537, 9), (2049, 10),
+    (3073, 10), (4097, 11), (6145, 11), (8193, 12), (12289, 12), (16385, 13),
+    (24577, 13),
+];
+
+/// Write a literal, a length or the end of the block with the fixed Huffman
+/// codes.
+fn write_symbol(bits: &mut Bits, symbol: u32) {
+    match symbol {
+        0..=143 => bits.write_code(0x30 + symbol, 8),
+        144..=255 => bits.write_code(0x190 + symbol - 144, 9),
+        256..=279 => bits.write_code(symbol - 256, 7),
+        _ => bits.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+/// Write a repeat of `length` bytes from `distance` bytes back.
+fn write_match(bits: &mut Bits, length: u32, distance: u32) {
+    let code = LENGTHS.partition_point(|(start, _)| *start <= length) - 1;
+    let (start, extra) = LENGTHS[code];
+    write_symbol(bits, 257 + code as u32);
+    bits.write(length - start, extra);
+    let code = DISTANCES.partition_point(|(start, _)| *start <= distance) - 1;
+    let (start, extra) = DISTANCES[code];
+    bits.write_code(code as u32, 5);
+    bits.write(distance - start, extra);
+}
+
+/// The bytes as zlib data, compressed in a single block with the fixed
+/// Huffman codes. Repeats are found with a hash of the next three bytes,
+/// which is plenty for pictures that are mostly runs of the same colors.
+fn zlib(bytes: &[u8]) -> Vec<u8> {
+    const WINDOW: usize = 32_768;
+    const TRIES: usize = 16;
+    const HASH_BITS: u32 = 15;
+    let hash = |position: usize| {
+        let key =
+            u32::from_le_bytes([bytes[position], bytes[position + 1], bytes[position + 2], 0]);
+        (key.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    };
+    // The last position with every hash, and for the positions in the
+    // window the one before it with the same hash, both off by one so zero
+    // means none.
+    let mut last = vec![0usize; 1 << HASH_BITS];
+    let mut previous = vec![0usize; WINDOW];
+    let mut bits = Bits::default();
+    // The final block, with the fixed codes.
+    bits.write(1, 1);
+    bits.write(1, 2);
+    let mut position = 0;
+    while position < bytes.len() {
+        let mut best = (0, 0);
+        if position + 3 <= bytes.len() {
+            let mut candidate = last[hash(position)];
+            for _ in 0..TRIES {
+                let Some(start) = candidate.checked_sub(1) else {
+                    break;
+                };
+                if position - start > WINDOW {
+                    break;
+                }
+                let longest = (bytes.len() - position).min(258);
+                // Runs mostly repeat as far as they can, which is quicker to
+                // check all at once.
+                let length = if bytes[start..start + longest] == bytes[position..position + longest]
+                {
+                    longest
+                } else {
+                    bytes[start..]
+                        .iter()
+                        .zip(&bytes[position..])
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                };
+                if length > best.0 {
+                    best = (length, position - start);
+                    if length == 258 {
+                        break;
+                    }
+                }
+                candidate = previous[start % WINDOW];
+            }
+        }
+        let (length, distance) = best;
+        if length >= 3 {
+            write_match(&mut bits, length as u32, distance as u32);
+        } else {
+            write_symbol(&mut bits, u32::from(bytes[position]));
+        }
+        // Remembering every position of long repeats takes long and gains
+        // little, so only remember their end.
+        let skipped = if length > 32 {
+            position + length - 3..position + length
+        } else {
+            position..position + length.max(1)
+        };
+        for skipped in skipped {
+            if skipped + 3 <= bytes.len() {
+                let hash = hash(skipped);
+                previous[skipped % WINDOW] = last[hash];
+                last[hash] = skipped + 1;
+            }
+        }
+        position += length.max(1);
+    }
+    write_symbol(&mut bits, 256);
+
+    let mut data = vec![0x78, 0x01];
+    data.extend(bits.finish());
+    data.extend(adler32(bytes).to_be_bytes());
+    data
+}
+
+fn write_chunk(output: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    output.write_all(&(data.len() as u32).to_be_bytes())?;
+    output.write_all(kind)?;
+    output.write_all(data)?;
+    output.write_all(&crc32(&[kind.as_slice(), data].concat()).to_be_bytes())
+}
+
+/// Write the signature and the header of a PNG of 8 bits per channel of
+/// RGB.
+fn write_header(width: usize, height: usize, output: &mut impl Write) -> io::Result<()> {
+    if width == 0 || height == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "an image needs at least a pixel",
+        ));
+    }
+    output.write_all(b"\x89PNG\r\n\x1a\n")?;
+    let header = [
+        (width as u32).to_be_bytes().as_slice(),
+        &(height as u32).to_be_bytes(),
+        // With the only compression and filter methods, without
+        // interlacing.
+        &[8, 2, 0, 0, 0],
+    ]
+    .concat();
+    write_chunk(output, b"IHDR", &header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The kinds of the chunks of a PNG, after checking their checksums.
+    fn chunks(png: &[u8]) -> Vec<String> {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let mut kinds = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (chunk, crc) = rest[4..8 + length + 4].split_at(4 + length);
+            assert_eq!(crc32(chunk).to_be_bytes(), crc);
+            kinds.push(String::from_utf8(chunk[..4].to_vec()).unwrap());
+            rest = &rest[12 + length..];
+        }
+        kinds
+    }
+
+    #[test]
+    fn encode_images() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+        let mut image = Image::new(2, 1, BLACK);
+        image.set(1, 0, heat(1.0));
+        let mut ppm = Vec::new();
+        image.scaled(2).write_ppm(&mut ppm).unwrap();
+        assert_eq!(&ppm[..11], b"P6\n4 2\n255\n");
+        assert_eq!(ppm.len(), 11 + 8 * 3);
+        assert_eq!(&ppm[11 + 6..11 + 9], &WHITE);
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert_eq!(chunks(&png), ["IHDR", "IDAT", "IEND"]);
+        // Runs of the same color take up next to nothing.
+        let mut png = Vec::new();
+        Image::new(100, 100, gray(0.5)).write_png(&mut png).unwrap();
+        assert!(png.len() < 1_000, "{}", png.len());
+
+        let mut animation = Animation::default();
+        animation.push(&image);
+        animation.push(&image.scaled(3));
+        let mut png = Vec::new();
+        animation.write(5, &mut png).unwrap();
+        assert_eq!(
+            chunks(&png),
+            ["IHDR", "acTL", "fcTL", "IDAT", "fcTL", "fdAT", "IEND"]
+        );
+        assert!(Image::new(0, 3, BLACK).write_png(&mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    env,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+/// The data directory used when none is given.
+pub const DEFAULT_DATA_DIRECTORY: &str = "data";
+
+/// The environment variable that overrides the default data directory.
+pub const DATA_DIRECTORY_VARIABLE: &str = "AOC_DATA";
+
+/// The dataset used when no input is given, the personal puzzle input.
+pub const DEFAULT_DATASET: &str = "real";
+
+/// Where the puzzle input for a day comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// A file anywhere on disk.
+    Path(PathBuf),
+    Stdin,
+    /// A named input for the day in the data directory, like `example` or
+    /// `real`.
+    Dataset(String),
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::Dataset(DEFAULT_DATASET.to_string())
+    }
+}
+
+impl Input {
+    /// The input for an `--input` argument, where `-` means stdin.
+    pub fn from_argument(argument: &str) -> Self {
+        if argument == "-" {
+            Input::Stdin
+        } else {
+            Input::Path(PathBuf::from(argument))
+        }
+    }
+
+    /// What went wrong reading the input for a day.
+    pub fn error(&self, day: u8, data_directory: &Path, error: io::Error) -> InputError {
+        InputError {
+            input: self.clone(),
+            location: self.location(day, data_directory),
+            error,
+        }
+    }
+
+    /// Read the whole input for a day.
+    pub fn read(&self, day: u8, data_directory: &Path) -> Result<String, InputError> {
+        let error = |error| self.error(day, data_directory, error);
+        match self {
+            Input::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map_err(error)?;
+                Ok(input)
+            }
+            Input::Path(path) => fs::read_to_string(path).map_err(error),
+            Input::Dataset(name) => {
+                fs::read_to_string(dataset_path(data_directory, day, name)).map_err(error)
+            }
+        }
+    }
+
+    /// Open the input for a day to read it a bit at a time.
+    pub fn open(&self, day: u8, data_directory: &Path) -> Result<Box<dyn BufRead>, InputError> {
+        let file = match self {
+            Input::Stdin => return Ok(Box::new(io::stdin().lock())),
+            Input::Path(path) => File::open(path),
+            Input::Dataset(name) => File::open(dataset_path(data_directory, day, name)),
+        };
+        let file = file.map_err(|error| self.error(day, data_directory, error))?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+
+    /// The file the input is read from, if it's read from a file.
+    pub fn location(&self, day: u8, data_directory: &Path) -> Option<PathBuf> {
+        match self {
+            Input::Stdin => None,
+            Input::Path(path) => Some(path.clone()),
+            Input::Dataset(name) => Some(dataset_path(data_directory, day, name)),
+        }
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Path(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "stdin"),
+            Input::Dataset(name) => write!(f, "dataset {name}"),
+        }
+    }
+}
+
+/// The reason an input couldn't be read.
+#[derive(Debug)]
+pub struct InputError {
+    pub input: Input,
+    pub location: Option<PathBuf>,
+    pub error: io::Error,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) if !matches!(self.input, Input::Path(_)) => write!(
+                f,
+                "Failed to read {} ({}): {}",
+                self.input,
+                location.display(),
+                self.error
+            ),
+            _ => write!(f, "Failed to read {}: {}", self.input, self.error),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// The hash of an empty input, to extend a piece at a time with
+/// [`extend_hash`].
+pub const EMPTY_INPUT_HASH: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// The hash of an input with the bytes added to the end.
+pub fn extend_hash(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The 64-bit FNV-1a hash of an input, to tell inputs apart without storing
+/// them.
+pub fn input_hash(input: &str) -> u64 {
+    extend_hash(EMPTY_INPUT_HASH, input.as_bytes())
+}
+
+/// The directory with the datasets, taken from the argument if there is one,
+/// otherwise from the environment, otherwise the default.
+pub fn data_directory(argument: Option<&Path>) -> PathBuf {
+    if let Some(directory) = argument {
+        directory.to_path_buf()
+    } else if let Some(directory) = env::var_os(DATA_DIRECTORY_VARIABLE) {
+        PathBuf::from(directory)
+    } else {
+        PathBuf::from(DEFAULT_DATA_DIRECTORY)
+    }
+}
+
+/// The file of a dataset, `<data directory>/day<day>/<name>.txt`.
+pub fn dataset_path(data_directory: &Path, day: u8, name: &str) -> PathBuf {
+    data_directory
+        .join(format!("day{day}"))
+        .join(format!("{name}.txt"))
+}
@@ -0,0 +1,390 @@
+//! Ranges of whole numbers, like sections or columns, and sets of them that
+//! stay merged so they can be measured all at once.
+//!
+//! Intervals are half-open: they hold their start but not their end, like
+//! `start..end`. Puzzles that give the first and the last number, like
+//! `2-4`, make them with [`Interval::inclusive`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    ops::{Add, Sub},
+};
+
+/// Whole numbers that intervals can be made of.
+pub trait Integer: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> {
+    /// The unsigned type of the same size, which can count all the numbers
+    /// between any two of this type.
+    type Unsigned: Integer;
+
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// How many numbers apart the two are.
+    fn abs_diff(self, other: Self) -> Self::Unsigned;
+}
+
+macro_rules! integer {
+    ($($type:ty => $unsigned:ty),*) => {
+        $(
+            impl Integer for $type {
+                type Unsigned = $unsigned;
+
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$type>::checked_add(self, other)
+                }
+
+                fn abs_diff(self, other: Self) -> $unsigned {
+                    <$type>::abs_diff(self, other)
+                }
+            }
+        )*
+    };
+}
+
+integer!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize
+);
+
+/// The numbers from `start` up to but not including `end`, which is empty
+/// when `end` isn't after `start`. See [`Interval::inclusive`] for intervals
+/// that include their last number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    /// The numbers from `start` up to but not including `end`, the same as
+    /// [`Interval::exclusive`].
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// The numbers from `start` up to but not including `end`.
+    pub fn exclusive(start: T, end: T) -> Self {
+        Self::new(start, end)
+    }
+
+    /// The numbers from `first` up to and including `last`.
+    ///
+    /// # Panics
+    ///
+    /// If `last` is the largest number of its type, use
+    /// [`Interval::try_inclusive`] for numbers that might be.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Self::try_inclusive(first, last).expect("The end of an interval fits in its type.")
+    }
+
+    /// The numbers from `first` up to and including `last`, if the number
+    /// after `last` fits in its type.
+    pub fn try_inclusive(first: T, last: T) -> Option<Self> {
+        Some(Self::new(first, last.checked_add(T::ONE)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// The amount of numbers in the interval, which can be more than fits in
+    /// `T` when it is signed.
+    pub fn len(&self) -> T::Unsigned {
+        if self.is_empty() {
+            T::Unsigned::default()
+        } else {
+            self.end.abs_diff(self.start)
+        }
+    }
+
+    /// The last number in the interval, if it isn't empty.
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::ONE)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every number of the other interval is in this one.
+    pub fn covers(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Whether there is a number in both intervals.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The numbers in both intervals.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The numbers in either interval, if that is an interval, so when they
+    /// overlap or one ends where the other starts.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.is_empty() {
+            Some(*other)
+        } else if other.is_empty() {
+            Some(*self)
+        } else if self.start <= other.end && other.start <= self.end {
+            Some(Self::new(
+                self.start.min(other.start),
+                self.end.max(other.end),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The numbers in this interval but not in the other, which are the
+    /// parts before and after it that aren't empty.
+    pub fn difference(&self, other: &Self) -> impl Iterator<Item = Self> {
+        let (before, after) = if other.is_empty() {
+            (*self, Self::new(self.end, self.end))
+        } else {
+            (
+                Self::new(self.start, self.end.min(other.start)),
+                Self::new(self.start.max(other.end), self.end),
+            )
+        };
+        [before, after]
+            .into_iter()
+            .filter(|interval| !interval.is_empty())
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Numbers kept as the fewest intervals that hold them, which never overlap
+/// or touch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    /// The end of every interval by its start.
+    intervals: BTreeMap<T, T>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The intervals in the set that overlap or touch the interval, by
+    /// their start.
+    fn touching(&self, interval: &Interval<T>) -> Vec<T> {
+        self.intervals
+            .range(..=interval.end)
+            .rev()
+            .take_while(|(_, end)| **end >= interval.start)
+            .map(|(start, _)| *start)
+            .collect()
+    }
+
+    /// Add the numbers of the interval, merging it with the intervals it
+    /// overlaps or touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let mut merged = interval;
+        for start in self.touching(&interval) {
+            let end = self
+                .intervals
+                .remove(&start)
+                .expect("The interval is in the set.");
+            merged = Interval::new(merged.start.min(start), merged.end.max(end));
+        }
+        self.intervals.insert(merged.start, merged.end);
+    }
+
+    /// Take out the numbers of the interval, splitting the intervals it
+    /// falls in.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        for start in self.touching(&interval) {
+            let end = self
+                .intervals
+                .remove(&start)
+                .expect("The interval is in the set.");
+            for rest in Interval::new(start, end).difference(&interval) {
+                self.intervals.insert(rest.start, rest.end);
+            }
+        }
+    }
+
+    /// The interval of the set the value is in, if any.
+    pub fn find(&self, value: T) -> Option<Interval<T>> {
+        let (start, end) = self.intervals.range(..=value).next_back()?;
+        let interval = Interval::new(*start, *end);
+        interval.contains(value).then_some(interval)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.find(value).is_some()
+    }
+
+    /// Whether every number of the interval is in the set.
+    pub fn covers(&self, interval: &Interval<T>) -> bool {
+        interval.is_empty()
+            || self
+                .find(interval.start)
+                .is_some_and(|found| found.covers(interval))
+    }
+
+    /// Whether any number of the interval is in the set.
+    pub fn overlaps(&self, interval: &Interval<T>) -> bool {
+        // Only the last interval that starts before the end can reach into
+        // it, the ones before that end before it.
+        !interval.is_empty()
+            && self
+                .intervals
+                .range(..interval.end)
+                .next_back()
+                .is_some_and(|(_, end)| *end > interval.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The amount of numbers in the set. The intervals don't overlap, so
+    /// these always fit.
+    pub fn len(&self) -> T::Unsigned {
+        self.iter().fold(T::Unsigned::default(), |total, interval| {
+            total + interval.len()
+        })
+    }
+
+    /// The intervals of the set, from the lowest numbers up.
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        let mut set = Self::new();
+        intervals
+            .into_iter()
+            .for_each(|interval| set.insert(interval));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::property::{self, agree};
+
+    #[test]
+    fn combine_intervals() {
+        let (a, b) = (Interval::inclusive(2, 6), Interval::new(4, 10));
+        assert_eq!((a.len(), a.last()), (5u32, Some(6)));
+        assert!(a.contains(6) && !a.contains(7));
+        assert!(a.overlaps(&b) && !a.covers(&b));
+        assert!(b.covers(&Interval::new(5, 7)) && b.covers(&Interval::new(20, 0)));
+        assert_eq!(a.intersection(&b), Interval::new(4, 7));
+        assert_eq!(a.union(&b), Some(Interval::new(2, 10)));
+        assert_eq!(a.union(&Interval::new(7, 8)), Some(Interval::new(2, 8)));
+        assert_eq!(a.union(&Interval::new(8, 9)), None);
+        assert_eq!(
+            a.difference(&Interval::new(3, 5)).collect::<Vec<_>>(),
+            [Interval::new(2, 3), Interval::new(5, 7)]
+        );
+        assert_eq!(b.difference(&a).collect::<Vec<_>>(), [Interval::new(7, 10)]);
+        assert_eq!(a.difference(&Interval::new(0, 10)).count(), 0);
+        assert!(Interval::new(3, 3).is_empty());
+        assert_eq!(Interval::new(3, 1).len(), 0u32);
+        assert_eq!(Interval::try_inclusive(0, u8::MAX), None);
+        assert_eq!(Interval::exclusive(2, 7), a);
+        assert_eq!(Interval::new(i8::MIN, i8::MAX).len(), 255u8);
+        assert_eq!(Interval::inclusive(-3i64, 3).len(), 7u64);
+        assert_eq!(a.to_string(), "2..7");
+
+        let set = [
+            Interval::inclusive(2, 4),
+            Interval::inclusive(6, 8),
+            Interval::inclusive(3, 5),
+        ]
+        .into_iter()
+        .collect::<IntervalSet<_>>();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [Interval::new(2, 9)]);
+        assert_eq!(set.len(), 7u32);
+    }
+
+    #[test]
+    fn set_matches_numbers() {
+        // Every change inserts an interval when its kind is even, and removes
+        // it when it is odd.
+        property::check(
+            |rng| {
+                (0..rng.range(0..=10))
+                    .map(|_| {
+                        let start = rng.range(0..=30) as u8;
+                        (rng.below(2) as u8, start, start + rng.range(0..=10) as u8)
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |changes| {
+                let mut set = IntervalSet::new();
+                let mut numbers = BTreeSet::new();
+                for &(kind, start, end) in changes {
+                    let interval = Interval::new(start, end);
+                    if kind % 2 == 0 {
+                        set.insert(interval);
+                        numbers.extend(start..end);
+                    } else {
+                        set.remove(interval);
+                        numbers.retain(|number| !interval.contains(*number));
+                    }
+                }
+                agree(
+                    numbers.clone(),
+                    set.iter()
+                        .flat_map(|interval| interval.start..interval.end)
+                        .collect(),
+                )?;
+                agree(numbers.len(), usize::from(set.len()))?;
+                // The intervals are as few as possible, so none of them touch.
+                agree(
+                    true,
+                    set.iter()
+                        .zip(set.iter().skip(1))
+                        .all(|(before, after)| before.end < after.start),
+                )?;
+                let probe = Interval::new(10, 15);
+                agree(
+                    (probe.start..probe.end).all(|number| numbers.contains(&number)),
+                    set.covers(&probe),
+                )?;
+                agree(
+                    (probe.start..probe.end).any(|number| numbers.contains(&number)),
+                    set.overlaps(&probe),
+                )
+            },
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Solutions for Advent of Code 2022.
+//!
+//! Every day has its own module that exposes the parser and the solver for
+//! that day, so they can be used without going through the `aoc` binary.
+
+pub mod answers;
+pub mod arguments;
+pub mod bench;
+pub mod client;
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod interval;
+pub mod logging;
+pub mod memory;
+pub mod parallel;
+pub mod parsing;
+#[cfg(test)]
+mod property;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod verify;
+pub mod visualize;
+pub mod watch;
+
+pub use arguments::ProgramArguments;
+pub use error::{ParseError, ProgramError};
+pub use solution::{Answer, Part, Solution};
@@ -0,0 +1,147 @@
+//! The logger the `aoc` binary installs. Diagnostics go to stderr, so they
+//! never end up between the results on stdout.
+//!
+//! Every day logs under its own target, like `day9`, so the output of a
+//! single day can be turned up without drowning in the others.
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// The level used when none is given, which keeps the solvers quiet.
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Warn;
+
+/// The environment variable with the levels, which `-v`, `-q` and `--log`
+/// override.
+pub const LOG_VARIABLE: &str = "AOC_LOG";
+
+/// Which messages are shown, for every target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    /// The level of targets that don't have their own.
+    pub default: LevelFilter,
+    /// The levels of targets, which also apply to everything below them.
+    pub targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::verbosity(0)
+    }
+}
+
+impl Filter {
+    /// The filter for a number of `-v` minus the number of `-q`.
+    pub fn verbosity(verbosity: i8) -> Self {
+        let default = match verbosity {
+            ..=-2 => LevelFilter::Off,
+            -1 => LevelFilter::Error,
+            0 => DEFAULT_LEVEL,
+            1 => LevelFilter::Info,
+            2 => LevelFilter::Debug,
+            3.. => LevelFilter::Trace,
+        };
+        Self {
+            default,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Add directives like `day9=trace,day3=debug`. A level on its own sets
+    /// the default.
+    pub fn add_directives(&mut self, directives: &str) -> Result<(), String> {
+        let level = |level: &str| {
+            level
+                .parse::<LevelFilter>()
+                .map_err(|_| format!("{level} isn't a log level."))
+        };
+        for directive in directives
+            .split(',')
+            .filter(|directive| !directive.is_empty())
+        {
+            match directive.split_once('=') {
+                Some((target, value)) => self.targets.push((target.to_string(), level(value)?)),
+                None => self.default = level(directive)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// The level of a target, from the most specific directive for it.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        let target = target.strip_prefix("aoc2022::").unwrap_or(target);
+        self.targets
+            .iter()
+            .filter(|(name, _)| {
+                target
+                    .strip_prefix(name.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// The most detailed level of any target.
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .chain([self.default])
+            .max()
+            .unwrap_or(self.default)
+    }
+}
+
+pub struct Logger {
+    filter: Filter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let target = record.target();
+            eprintln!(
+                "[{} {}] {}",
+                record.level(),
+                target.strip_prefix("aoc2022::").unwrap_or(target),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the logger with the given filter.
+pub fn init(filter: Filter) -> Result<(), SetLoggerError> {
+    // Messages above the most detailed level are skipped before they're even
+    // formatted, so quiet runs don't pay for tracing.
+    log::set_max_level(filter.max_level());
+    log::set_boxed_logger(Box::new(Logger { filter }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_levels() {
+        let mut filter = Filter::verbosity(-1);
+        filter
+            .add_directives("day9=trace,day1=debug,day1::parse=off")
+            .unwrap();
+
+        assert_eq!(filter.level("aoc2022::day9"), LevelFilter::Trace);
+        assert_eq!(filter.level("aoc2022::day1"), LevelFilter::Debug);
+        assert_eq!(filter.level("aoc2022::day10"), LevelFilter::Error);
+        assert_eq!(filter.level("aoc2022::day1::parse"), LevelFilter::Off);
+        assert_eq!(filter.level("ureq::unit"), LevelFilter::Error);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+
+        filter.add_directives("info").unwrap();
+        assert_eq!(filter.level("aoc2022::day10"), LevelFilter::Info);
+        assert!(filter.add_directives("day9=loud").is_err());
+    }
+}
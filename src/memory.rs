@@ -0,0 +1,144 @@
+//! Keeping track of how much memory is allocated, to measure the most memory
+//! something needs at once.
+//!
+//! Only allocations through [`CountingAllocator`] are counted, so it has to be
+//! the global allocator for any of this to measure something. It only counts
+//! once [`start_counting`] was called, so programs that don't measure memory
+//! only pay for checking a flag.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicIsize, Ordering},
+};
+
+/// Whether allocations are counted.
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+/// The bytes allocated right now, minus what was allocated before counting
+/// started and freed since, which can make it negative.
+static ALLOCATED: AtomicIsize = AtomicIsize::new(0);
+
+/// The most bytes allocated at once since the last measurement started.
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+/// The system allocator, counting the bytes it hands out.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn allocated(size: usize) {
+        if COUNTING.load(Ordering::Relaxed) {
+            let size = size as isize;
+            let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+    }
+
+    fn freed(size: usize) {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATED.fetch_sub(size as isize, Ordering::Relaxed);
+        }
+    }
+}
+
+// SAFETY: Every call is passed on to the system allocator unchanged, only the
+// sizes are counted on the side.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            Self::allocated(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            Self::allocated(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        Self::freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            Self::allocated(new_size);
+            Self::freed(layout.size());
+        }
+        new_pointer
+    }
+}
+
+/// Count allocations from now on. There is no stopping, since what is
+/// allocated while counting has to be counted when it is freed too.
+pub fn start_counting() {
+    COUNTING.store(true, Ordering::Relaxed);
+}
+
+pub fn is_counting() -> bool {
+    COUNTING.load(Ordering::Relaxed)
+}
+
+/// Run `f`, returning its output with the most bytes that were allocated at
+/// once while it ran, on top of what was allocated before. There is no peak
+/// when allocations aren't counted.
+///
+/// Allocations on other threads count too, so this is only accurate when
+/// nothing else is running.
+pub fn peak_during<T>(f: impl FnOnce() -> T) -> (T, Option<usize>) {
+    if !is_counting() {
+        return (f(), None);
+    }
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let output = f();
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(before);
+    (output, Some(peak.max(0) as usize))
+}
+
+/// A size in bytes in the largest unit it is at least one of, like `1.5 MiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_peaks() {
+        // Other tests allocate at the same time, which can only make the
+        // peak higher, or lower by what they free in the meantime.
+        start_counting();
+        let (length, peak) = peak_during(|| {
+            let big = std::hint::black_box(vec![1u8; 1 << 20]);
+            drop(vec![0u8; 1 << 10]);
+            big.len()
+        });
+        assert_eq!(length, 1 << 20);
+        assert!(peak.is_some_and(|peak| peak >= 1 << 19), "{peak:?}");
+
+        assert_eq!(format_bytes(12), "12 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 30), "3.0 GiB");
+    }
+}
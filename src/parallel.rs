@@ -0,0 +1,390 @@
+//! Running many days at once on a pool of threads, with a day that panics
+//! reported like any other failure instead of taking the others down.
+
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    input::Input,
+    solution::{Answer, DayReport, Part, RegisteredSolution},
+};
+
+/// How a day that was run ended.
+pub enum DayOutcome {
+    Report(DayReport),
+    /// The input couldn't be read.
+    InputError(String),
+    /// The day panicked, with the message of the panic.
+    Panic(String),
+}
+
+pub struct DayRun {
+    pub day: u8,
+    pub outcome: DayOutcome,
+    /// How long the day took, from reading the input to the last part.
+    pub time: Duration,
+    /// The CPU time the day used over that time, if the system can tell.
+    pub cpu_time: Option<Duration>,
+}
+
+impl DayRun {
+    pub fn is_success(&self) -> bool {
+        matches!(&self.outcome, DayOutcome::Report(report) if report.is_success())
+    }
+
+    /// The report of the day, or its day and error if it has none.
+    pub fn to_json(&self) -> Value {
+        match &self.outcome {
+            DayOutcome::Report(report) => report.to_json(),
+            DayOutcome::InputError(error) | DayOutcome::Panic(error) => {
+                json!({ "day": self.day, "error": error })
+            }
+        }
+    }
+}
+
+/// The message a panic was started with.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
+
+thread_local! {
+    /// Whether a panic on this thread is caught by [`catch_panic`].
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f`, returning the message of its panic if it panics. The panic isn't
+/// printed, reporting it is up to the caller.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    // The hook is shared by every thread, so it stays in place and only keeps
+    // quiet about the panics that are caught.
+    static QUIET_HOOK: Once = Once::new();
+    QUIET_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                default_hook(info);
+            }
+        }));
+    });
+    let catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(catching);
+    result.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// The CPU time the current thread used so far, if the system can tell.
+pub fn thread_cpu_time() -> Option<Duration> {
+    #[cfg(unix)]
+    {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: the time is only written to, and lives through the call.
+        let result = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+        (result == 0).then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+    }
+    #[cfg(not(unix))]
+    None
+}
+
+/// The amount of threads to run days on when none is given.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, usize::from)
+}
+
+fn run_day(
+    solution: &RegisteredSolution,
+    input: &Input,
+    data_directory: &Path,
+    parts: &[Part],
+) -> DayRun {
+    let start = Instant::now();
+    let start_cpu = thread_cpu_time();
+    let outcome = match input.read(solution.day, data_directory) {
+        Ok(input) => catch_panic(|| solution.run(&input, parts))
+            .map_or_else(DayOutcome::Panic, DayOutcome::Report),
+        Err(error) => DayOutcome::InputError(error.to_string()),
+    };
+    DayRun {
+        day: solution.day,
+        outcome,
+        time: start.elapsed(),
+        cpu_time: thread_cpu_time()
+            .zip(start_cpu)
+            .map(|(end, start)| end - start),
+    }
+}
+
+/// Run the solutions on `jobs` threads, each on its own input, returning
+/// the runs in the order of the solutions.
+pub fn run_all(
+    solutions: &[&RegisteredSolution],
+    input: &Input,
+    data_directory: &Path,
+    parts: &[Part],
+    jobs: usize,
+) -> Vec<DayRun> {
+    // Every thread takes the next day that nobody took yet, until there are
+    // none left.
+    let next = AtomicUsize::new(0);
+    let mut runs = thread::scope(|scope| {
+        let workers = (0..jobs.clamp(1, solutions.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut runs = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(solution) = solutions.get(index) else {
+                            return runs;
+                        };
+                        runs.push((index, run_day(solution, input, data_directory, parts)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Panics of days are caught."))
+            .collect::<Vec<_>>()
+    });
+    runs.sort_by_key(|(index, _)| *index);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+/// The answers and timings of the runs as a table, followed by the answers
+/// and errors that don't fit on a line.
+pub struct RunTable<'a> {
+    pub runs: &'a [DayRun],
+    pub parts: &'a [Part],
+    /// How long running all the days took.
+    pub wall_time: Duration,
+    pub jobs: usize,
+}
+
+impl Display for RunTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The cells of the answers, with the details to show below.
+        let mut details = Vec::new();
+        let rows = self
+            .runs
+            .iter()
+            .map(|run| {
+                let report = match &run.outcome {
+                    DayOutcome::Report(report) => report,
+                    DayOutcome::InputError(error) => {
+                        details.push(format!("Day {}: input error: {error}", run.day));
+                        return vec!["NO INPUT".to_string(); self.parts.len()];
+                    }
+                    DayOutcome::Panic(message) => {
+                        details.push(format!("Day {}: panicked: {message}", run.day));
+                        return vec!["PANICKED".to_string(); self.parts.len()];
+                    }
+                };
+                if let Some(error) = &report.parse_error {
+                    details.push(format!("Day {}: parse error: {error}", run.day));
+                    return vec!["PARSE ERROR".to_string(); self.parts.len()];
+                }
+                report
+                    .parts
+                    .iter()
+                    .map(|(part, answer, _)| match answer {
+                        Ok(Answer::Text(text)) if text.contains('\n') => {
+                            let lines = text
+                                .lines()
+                                .map(|line| format!("\n  {line}"))
+                                .collect::<String>();
+                            details.push(format!("Day {}, part {part}:{lines}", run.day));
+                            "(below)".to_string()
+                        }
+                        Ok(answer) => answer.to_string(),
+                        Err(error) => {
+                            details.push(format!("Day {}, part {part}: error: {error}", run.day));
+                            "ERROR".to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let widths = self
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(column, part)| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(String::len)
+                    .chain([format!("Part {part}").len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "Day")?;
+        for (part, width) in self.parts.iter().zip(&widths) {
+            write!(f, "  {:width$}", format!("Part {part}"))?;
+        }
+        write!(f, "  {:>10}", "Parse")?;
+        for part in self.parts {
+            write!(f, "  {:>10}", format!("Part {part}"))?;
+        }
+        writeln!(f, "  {:>10}", "Total")?;
+        for (run, row) in self.runs.iter().zip(&rows) {
+            write!(f, "{:>3}", run.day)?;
+            for (column, width) in widths.iter().enumerate() {
+                write!(f, "  {:width$}", row.get(column).map_or("", String::as_str))?;
+            }
+            let time =
+                |time: Option<Duration>| time.map_or(String::new(), |time| format!("{time:.2?}"));
+            let report = match &run.outcome {
+                DayOutcome::Report(report) => Some(report),
+                _ => None,
+            };
+            write!(f, "  {:>10}", time(report.map(|report| report.parse_time)))?;
+            for column in 0..self.parts.len() {
+                let part_time = report.and_then(|report| report.parts.get(column));
+                write!(f, "  {:>10}", time(part_time.map(|(_, _, time)| *time)))?;
+            }
+            writeln!(f, "  {:>10}", time(Some(run.time)))?;
+        }
+
+        for detail in &details {
+            writeln!(f, "\n{detail}")?;
+        }
+        // The threads use more CPU time than the wall time when they run at
+        // once.
+        let cpu_time = self
+            .runs
+            .iter()
+            .map(|run| run.cpu_time)
+            .sum::<Option<Duration>>()
+            .map_or("unknown".to_string(), |time| format!("{time:.2?}"));
+        let succeeded = self.runs.iter().filter(|run| run.is_success()).count();
+        let threads = if self.jobs == 1 { "thread" } else { "threads" };
+        write!(
+            f,
+            "\nWall time {:.2?}, CPU time {cpu_time} on {} {threads}. \
+             {succeeded} of {} days succeeded.",
+            self.wall_time,
+            self.jobs,
+            self.runs.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        generate::Generator,
+        solution::{run, PartResult, Solution},
+        ParseError,
+    };
+
+    /// A day that panics in its second part.
+    struct Panicking;
+
+    impl Solution for Panicking {
+        type Input<'a> = usize;
+
+        fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+            Ok(input.len())
+        }
+
+        fn part1(length: &Self::Input<'_>) -> PartResult {
+            Ok((*length).into())
+        }
+
+        fn part2(_length: &Self::Input<'_>) -> PartResult {
+            panic!("Not like this.")
+        }
+    }
+
+    #[test]
+    fn catch_panics() {
+        assert_eq!(catch_panic(|| 3), Ok(3));
+        assert_eq!(
+            catch_panic(|| -> u8 { panic!("Not like this.") }),
+            Err("Not like this.".to_string())
+        );
+        assert_eq!(
+            catch_panic(|| catch_panic(|| panic!("Inner.")).map(|_: ()| ())),
+            Ok(Err("Inner.".to_string()))
+        );
+    }
+
+    #[test]
+    fn isolate_panics() {
+        let panicking = RegisteredSolution {
+            day: 3,
+            run: run::<Panicking>,
+            bench: crate::bench::measure::<Panicking>,
+            generator: Generator {
+                generate: |_, _| String::new(),
+                real_size: 0,
+            },
+            visualize: None,
+            draw: None,
+            stream: None,
+        };
+        let day1 = crate::solution::solution(1).unwrap();
+        let day10 = crate::solution::solution(10).unwrap();
+        let data_directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("data");
+        let runs = run_all(
+            &[day10, &panicking, day1],
+            &Input::Dataset("example".to_string()),
+            &data_directory,
+            &Part::ALL,
+            2,
+        );
+        assert_eq!(
+            runs.iter().map(|run| run.day).collect::<Vec<_>>(),
+            [10, 3, 1]
+        );
+        assert!(runs[0].is_success() && runs[2].is_success());
+        assert!(
+            matches!(&runs[1].outcome, DayOutcome::Panic(message) if message == "Not like this.")
+        );
+        assert_eq!(runs.iter().all(|run| run.cpu_time.is_some()), cfg!(unix));
+
+        let table = RunTable {
+            runs: &runs,
+            parts: &Part::ALL,
+            wall_time: Duration::from_millis(1),
+            jobs: 2,
+        }
+        .to_string();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("Day  Part 1    Part 2    "), "{table}");
+        assert!(lines[1].starts_with(" 10  13140     (below)   "), "{table}");
+        assert!(lines[2].starts_with("  3  PANICKED  PANICKED  "), "{table}");
+        assert!(table.contains("\nDay 10, part 2:\n  ##..##"), "{table}");
+        assert!(
+            table.contains("\nDay 3: panicked: Not like this.\n"),
+            "{table}"
+        );
+        assert!(
+            table.contains(", CPU time ")
+                && table.ends_with("on 2 threads. 2 of 3 days succeeded."),
+            "{table}"
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! Small pieces to take puzzle inputs apart with, which all point at where
+//! in the input something went wrong when it isn't what they expected.
+
+use std::{
+    io::{self, BufRead},
+    str::{FromStr, Split},
+};
+
+use crate::{
+    grid::Grid,
+    input::{extend_hash, EMPTY_INPUT_HASH},
+    ParseError,
+};
+
+/// A line of the input and where it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// The number of the line, starting at 1.
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// An error for `part`, which has to be a slice of the line.
+    pub fn error(&self, part: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::in_line(self.number, self.text, part, expected)
+    }
+
+    /// The words of the line, separated by single spaces, to take from the
+    /// start.
+    pub fn words(self) -> Words<'a> {
+        Words {
+            line: self,
+            words: self.text.split(' '),
+        }
+    }
+
+    /// The line to take apart a character at a time, for lines like `2-4,6-8`
+    /// that aren't separated into words.
+    pub fn scan(self) -> Scanner<'a> {
+        Scanner {
+            line: self,
+            rest: self.text,
+        }
+    }
+
+    /// Where the line ends, to point at when something is missing.
+    fn end(&self) -> &'a str {
+        &self.text[self.text.len()..]
+    }
+}
+
+/// The lines of the input with their numbers.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(index, text)| Line {
+        number: index + 1,
+        text,
+    })
+}
+
+/// The lines of a reader with their numbers, read one at a time into the
+/// same buffer, so only a single line has to fit in memory.
+pub struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    number: usize,
+    hash: u64,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            number: 0,
+            hash: EMPTY_INPUT_HASH,
+        }
+    }
+
+    /// The next line without its line ending, like [`lines`], or `None` at
+    /// the end of the input.
+    pub fn next_line(&mut self) -> io::Result<Option<Line<'_>>> {
+        self.buffer.clear();
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(None);
+        }
+        self.hash = extend_hash(self.hash, self.buffer.as_bytes());
+        self.number += 1;
+        let text = match self.buffer.strip_suffix('\n') {
+            Some(text) => text.strip_suffix('\r').unwrap_or(text),
+            None => &self.buffer,
+        };
+        Ok(Some(Line {
+            number: self.number,
+            text,
+        }))
+    }
+
+    /// The hash of everything read so far, see
+    /// [`input_hash`](crate::input::input_hash).
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// The groups of lines separated by empty lines, like the inventories of the
+/// elves.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = Vec<Line<'_>>> {
+    let mut lines = lines(input).peekable();
+    std::iter::from_fn(move || {
+        while lines.next_if(|line| line.text.is_empty()).is_some() {}
+        lines.peek()?;
+        Some(std::iter::from_fn(|| lines.next_if(|line| !line.text.is_empty())).collect())
+    })
+}
+
+/// Every run of digits in the line, where `signed` takes a `-` right before
+/// them along.
+fn numbers<'a>(line: &Line<'a>, signed: bool) -> Vec<&'a str> {
+    let bytes = line.text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if !bytes[index].is_ascii_digit() {
+            index += 1;
+            continue;
+        }
+        let start = if signed && index > 0 && bytes[index - 1] == b'-' {
+            index - 1
+        } else {
+            index
+        };
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        numbers.push(&line.text[start..index]);
+    }
+    numbers
+}
+
+/// Every number in the line, ignoring whatever is around them. Numbers that
+/// don't fit in `T` are `expected` to be something else.
+pub fn integers<T: FromStr>(line: &Line, expected: &str) -> Result<Vec<T>, ParseError> {
+    numbers(line, false)
+        .into_iter()
+        .map(|number| number.parse().map_err(|_| line.error(number, expected)))
+        .collect()
+}
+
+/// Every number in the line like [`integers`], with the ones right after a
+/// `-` negative.
+pub fn signed_integers<T: FromStr>(line: &Line, expected: &str) -> Result<Vec<T>, ParseError> {
+    numbers(line, true)
+        .into_iter()
+        .map(|number| number.parse().map_err(|_| line.error(number, expected)))
+        .collect()
+}
+
+/// The line cut into columns of `width` characters, where the last one can
+/// be shorter, like the drawings of stacks of crates.
+pub fn fixed_width_columns<'a>(line: &Line<'a>, width: usize) -> Vec<&'a str> {
+    let starts = line
+        .text
+        .char_indices()
+        .map(|(index, _)| index)
+        .step_by(width.max(1))
+        .chain([line.text.len()])
+        .collect::<Vec<_>>();
+    starts
+        .windows(2)
+        .map(|bounds| &line.text[bounds[0]..bounds[1]])
+        .collect()
+}
+
+/// A grid of a digit per cell, where anything else isn't what was
+/// `expected`.
+pub fn grid_of_digits(input: &str, expected: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(
+        input,
+        |char| char.to_digit(10).map(|digit| digit as u8),
+        expected,
+    )
+}
+
+/// The keywords as a choice, like `U, D, L or R`.
+fn one_of(keywords: &[&str]) -> String {
+    match keywords {
+        [] => "nothing".to_string(),
+        [keyword] => keyword.to_string(),
+        [rest @ .., last] => format!("{} or {last}", rest.join(", ")),
+    }
+}
+
+/// A line like `addx 3` of one of the keywords and a number, returning both.
+pub fn keyword_then_int<'a, T: FromStr>(
+    line: &Line<'a>,
+    keywords: &[&'a str],
+    expected: &str,
+) -> Result<(&'a str, T), ParseError> {
+    let mut words = line.words();
+    let keyword = words.keyword(keywords)?;
+    let number = words.integer(expected)?;
+    words.end()?;
+    Ok((keyword, number))
+}
+
+/// The words of a line, taken one at a time.
+pub struct Words<'a> {
+    line: Line<'a>,
+    words: Split<'a, char>,
+}
+
+impl<'a> Words<'a> {
+    /// The next word, which is `expected` to be there.
+    pub fn word(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        self.words
+            .next()
+            .filter(|word| !word.is_empty())
+            .ok_or_else(|| self.line.error(self.line.end(), expected))
+    }
+
+    /// The next word, which has to be one of the keywords.
+    pub fn keyword(&mut self, keywords: &[&'a str]) -> Result<&'a str, ParseError> {
+        let word = self.words.next().unwrap_or(self.line.end());
+        keywords
+            .iter()
+            .find(|keyword| **keyword == word)
+            .copied()
+            .ok_or_else(|| self.line.error(word, one_of(keywords)))
+    }
+
+    /// The next word as a number.
+    pub fn integer<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let word = self.words.next().unwrap_or(self.line.end());
+        word.parse().map_err(|_| self.line.error(word, expected))
+    }
+
+    /// Make sure there are no words left.
+    pub fn end(mut self) -> Result<(), ParseError> {
+        match self.words.next() {
+            Some(rest) => Err(self.line.error(rest, "the end of the line")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The rest of a line, taken from the start.
+pub struct Scanner<'a> {
+    line: Line<'a>,
+    rest: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    /// The next character, or where the line ends if there are none left.
+    fn next_char(&self) -> &'a str {
+        &self.rest[..self.rest.chars().next().map_or(0, char::len_utf8)]
+    }
+
+    /// The digits at the start as a number.
+    pub fn integer<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let end = self
+            .rest
+            .find(|char: char| !char.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let (digits, rest) = self.rest.split_at(end);
+        let part = if digits.is_empty() {
+            self.next_char()
+        } else {
+            digits
+        };
+        let number = digits
+            .parse()
+            .map_err(|_| self.line.error(part, expected))?;
+        self.rest = rest;
+        Ok(number)
+    }
+
+    /// Take the separator, which has to be next.
+    pub fn separator(&mut self, separator: char) -> Result<(), ParseError> {
+        self.rest = self
+            .rest
+            .strip_prefix(separator)
+            .ok_or_else(|| self.line.error(self.next_char(), format!("{separator:?}")))?;
+        Ok(())
+    }
+
+    /// Make sure there is nothing left.
+    pub fn end(self) -> Result<(), ParseError> {
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.line.error(self.rest, "the end of the line"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_apart_lines() {
+        let input = "move 3 from -1 to 20\n\n\nnoop\naddx -4\naddx 4 5\n";
+        let lines = lines(input).collect::<Vec<_>>();
+        assert_eq!(integers::<u8>(&lines[0], "a number"), Ok(vec![3, 1, 20]));
+        assert_eq!(
+            signed_integers::<i8>(&lines[0], "a number"),
+            Ok(vec![3, -1, 20])
+        );
+        assert_eq!(
+            integers::<u8>(
+                &Line {
+                    number: 7,
+                    text: "1-300"
+                },
+                "a small number"
+            ),
+            Err(ParseError::new(7, 3, "300", "a small number"))
+        );
+        assert_eq!(
+            paragraphs(input)
+                .map(|paragraph| paragraph.iter().map(|line| line.number).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [vec![1], vec![4, 5, 6]]
+        );
+        assert_eq!(
+            fixed_width_columns(
+                &Line {
+                    number: 1,
+                    text: "[A]     [B]"
+                },
+                4
+            ),
+            ["[A] ", "    ", "[B]"]
+        );
+
+        assert_eq!(
+            keyword_then_int(&lines[4], &["noop", "addx"], "a number"),
+            Ok(("addx", -4))
+        );
+        assert_eq!(
+            keyword_then_int::<i32>(&lines[3], &["noop", "addx"], "a number"),
+            Err(ParseError::new(4, 5, "", "a number"))
+        );
+        assert_eq!(
+            keyword_then_int::<i32>(&lines[5], &["addx"], "a number"),
+            Err(ParseError::new(6, 8, "5", "the end of the line"))
+        );
+        assert_eq!(
+            keyword_then_int::<i32>(&lines[0], &["U", "D", "L", "R"], "a number"),
+            Err(ParseError::new(1, 1, "move", "U, D, L or R"))
+        );
+    }
+
+    #[test]
+    fn scan_characters() {
+        let line = Line {
+            number: 2,
+            text: "12-x",
+        };
+        let mut scanner = line.scan();
+        assert_eq!(scanner.integer::<u8>("a number"), Ok(12));
+        assert_eq!(
+            scanner.separator(','),
+            Err(ParseError::new(2, 3, "-", "','"))
+        );
+        assert_eq!(scanner.separator('-'), Ok(()));
+        assert_eq!(
+            scanner.integer::<u8>("a number"),
+            Err(ParseError::new(2, 4, "x", "a number"))
+        );
+        assert_eq!(
+            scanner.end(),
+            Err(ParseError::new(2, 4, "x", "the end of the line"))
+        );
+    }
+
+    #[test]
+    fn read_lines_one_at_a_time() {
+        let input = "noop\r\n\naddx 3";
+        let mut reader = LineReader::new(input.as_bytes());
+        let mut read = Vec::new();
+        while let Some(line) = reader.next_line().unwrap() {
+            read.push((line.number, line.text.to_string()));
+        }
+        assert_eq!(
+            read,
+            lines(input)
+                .map(|line| (line.number, line.text.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(reader.hash(), crate::input::input_hash(input));
+    }
+
+    #[test]
+    fn digits_make_grids() {
+        let grid = grid_of_digits("123\n456\n", "a digit").unwrap();
+        assert_eq!(grid.to_string(), "123\n456");
+        assert_eq!(
+            grid_of_digits("12\n4x", "a digit"),
+            Err(ParseError::new(2, 2, "x", "a digit"))
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Property testing: check that something holds for many random values, and
+//! when it doesn't, shrink the value to a small counterexample.
+//!
+//! Values are made with the same [`Rng`] as the input generators, seeded
+//! with the number of the case, so a failure can always be made again.
+
+use std::fmt::Debug;
+
+use crate::{generate::Rng, parallel::catch_panic};
+
+/// The amount of random values every property is checked for.
+pub const CASES: u64 = 300;
+
+/// The most values tried while shrinking, so a property that is slow to
+/// check doesn't make shrinking take forever.
+const SHRINK_LIMIT: usize = 10_000;
+
+/// A value that can be made simpler, to find the smallest value for which a
+/// property fails.
+pub trait Shrink: Clone + Debug {
+    /// Values that are a bit simpler than this one, the simplest first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! shrink_unsigned {
+    ($($type:ty),*) => {$(
+        impl Shrink for $type {
+            fn shrink(&self) -> Vec<Self> {
+                let mut simpler = vec![0, self / 2, self.saturating_sub(1)];
+                simpler.dedup();
+                simpler.retain(|value| value < self);
+                simpler
+            }
+        }
+    )*};
+}
+
+shrink_unsigned!(u8, usize);
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        match *self {
+            'a' => Vec::new(),
+            'b' => vec!['a'],
+            _ => vec!['a', 'b'],
+        }
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b) = self;
+        let mut simpler = a
+            .shrink()
+            .into_iter()
+            .map(|a| (a, b.clone()))
+            .collect::<Vec<_>>();
+        simpler.extend(b.shrink().into_iter().map(|b| (a.clone(), b)));
+        simpler
+    }
+}
+
+impl<A: Shrink, B: Shrink, C: Shrink> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        ((self.0.clone(), self.1.clone()), self.2.clone())
+            .shrink()
+            .into_iter()
+            .map(|((a, b), c)| (a, b, c))
+            .collect()
+    }
+}
+
+impl<T: Shrink> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut simpler = Vec::new();
+        // Remove big chunks first, down to single elements, and only then
+        // try to make the elements themselves simpler.
+        let mut chunk = self.len() / 2;
+        while chunk > 0 {
+            for start in (0..self.len()).step_by(chunk) {
+                let mut smaller = self.clone();
+                smaller.drain(start..(start + chunk).min(self.len()));
+                simpler.push(smaller);
+            }
+            chunk /= 2;
+        }
+        if self.len() == 1 {
+            simpler.push(Vec::new());
+        }
+        for (index, element) in self.iter().enumerate() {
+            for element in element.shrink() {
+                let mut smaller = self.clone();
+                smaller[index] = element;
+                simpler.push(smaller);
+            }
+        }
+        simpler
+    }
+}
+
+/// Whether the property holds for the value, where a panic counts as not
+/// holding.
+fn holds<T>(property: &impl Fn(&T) -> Result<(), String>, value: &T) -> Result<(), String> {
+    catch_panic(|| property(value)).unwrap_or_else(|message| Err(format!("Panicked: {message}")))
+}
+
+/// Check the property for [`CASES`] generated values, and panic with the
+/// smallest value found for which it fails.
+///
+/// Properties that only make sense for some values can return `Ok(())` for
+/// the others, those are never reported.
+pub fn check<T: Shrink>(
+    generate: impl Fn(&mut Rng) -> T,
+    property: impl Fn(&T) -> Result<(), String>,
+) {
+    for seed in 0..CASES {
+        let mut value = generate(&mut Rng::new(seed));
+        let Err(mut message) = holds(&property, &value) else {
+            continue;
+        };
+
+        // Keep taking the first simpler value that still fails, until none
+        // of them do.
+        let mut tried = 0;
+        'shrink: while tried < SHRINK_LIMIT {
+            for simpler in value.shrink() {
+                tried += 1;
+                if let Err(simpler_message) = holds(&property, &simpler) {
+                    value = simpler;
+                    message = simpler_message;
+                    continue 'shrink;
+                }
+            }
+            break;
+        }
+        panic!("The property failed for seed {seed}, shrunk to:\n{value:?}\n{message}");
+    }
+}
+
+/// Fails when the naive and the fast result aren't the same.
+pub fn agree<V: PartialEq + Debug>(naive: V, fast: V) -> Result<(), String> {
+    if naive == fast {
+        Ok(())
+    } else {
+        Err(format!(
+            "The naive result is {naive:?}, but the fast one {fast:?}."
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrinks_to_smallest() {
+        let message = catch_panic(|| {
+            check(
+                |rng| (0..20).map(|_| rng.below(100) as usize).collect::<Vec<_>>(),
+                |numbers| {
+                    if numbers.iter().all(|number| *number < 50) {
+                        Ok(())
+                    } else {
+                        Err("Too big.".to_string())
+                    }
+                },
+            )
+        })
+        .unwrap_err();
+        assert!(message.contains("shrunk to:\n[50]\n"), "{message}");
+    }
+}
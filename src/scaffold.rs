@@ -0,0 +1,389 @@
+//! Adding a new day: a module with a stub solution and a failing test,
+//! registered everywhere the other days are, with an empty example to fill in.
+
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::answers::DEFAULT_ANSWERS_FILE;
+
+/// The reason a day couldn't be added.
+#[derive(Debug)]
+pub enum ScaffoldError {
+    InvalidDay(u8),
+    /// The day already has a module.
+    Exists(u8),
+    /// A file that has to be changed to register the day isn't there, or
+    /// doesn't look like expected.
+    Unrecognized {
+        path: PathBuf,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaffoldError::InvalidDay(day) => {
+                write!(f, "There is no day {day}, expected a day from 1 to 25.")
+            }
+            ScaffoldError::Exists(day) => write!(f, "Day {day} already exists."),
+            ScaffoldError::Unrecognized { path, reason } => {
+                write!(f, "Can't register the day in {}: {reason}", path.display())
+            }
+            ScaffoldError::Io { path, error } => {
+                write!(f, "Failed to write {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+/// The module of a new day, with a solution that doesn't solve anything yet.
+pub fn module_source(day: u8) -> String {
+    format!(
+        r#"use crate::{{
+    generate::Rng,
+    solution::{{PartResult, Solution}},
+    ParseError,
+}};
+
+/// Generate `size` lines of input.
+pub fn generate(rng: &mut Rng, size: usize) -> String {{
+    (0..size.max(1)).map(|_| rng.word(1..=10) + "\n").collect()
+}}
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input<'a> = Vec<&'a str>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {{
+        Ok(input.lines().collect())
+    }}
+
+    fn part1(_input: &Self::Input<'_>) -> PartResult {{
+        Err("Part 1 isn't solved yet.".into())
+    }}
+
+    fn part2(_input: &Self::Input<'_>) -> PartResult {{
+        Err("Part 2 isn't solved yet.".into())
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+    use crate::Answer;
+
+    const EXAMPLE: &str = include_str!("../data/day{day}/example.txt");
+
+    #[test]
+    fn part1_example() {{
+        let input = Day{day}::parse(EXAMPLE).unwrap();
+        // The answer to the example, from the puzzle.
+        assert_eq!(Day{day}::part1(&input).unwrap(), Answer::Number(0));
+    }}
+}}
+"#
+    )
+}
+
+/// The binary that only solves a new day, like the ones of the other days.
+fn binary_source(day: u8) -> String {
+    format!(
+        r#"use std::process::ExitCode;
+
+// Only solves day {day}, the aoc binary runs all of them.
+fn main() -> ExitCode {{
+    aoc2022::solution::solve_day({day})
+}}
+"#
+    )
+}
+
+/// The fuzz target of a new day, like the ones of the other days.
+fn fuzz_target_source(day: u8) -> String {
+    format!(
+        r#"#![no_main]
+
+use aoc2022::{{day{day}::Day{day}, Solution}};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {{
+    if let Ok(input) = std::str::from_utf8(data) {{
+        if let Ok(parsed) = Day{day}::parse(input) {{
+            let _ = Day{day}::part1(&parsed);
+            let _ = Day{day}::part2(&parsed);
+        }}
+    }}
+}});
+"#
+    )
+}
+
+fn is_day_module(name: &str) -> bool {
+    name.strip_prefix("day")
+        .is_some_and(|day| day.parse::<u8>().is_ok())
+}
+
+/// Add the module of the day to `lib.rs`, in the order `rustfmt` keeps the
+/// modules in.
+fn register_module(lib: &str, day: u8) -> Option<String> {
+    let module = format!("day{day}");
+    let mut lines = lib.lines().collect::<Vec<_>>();
+    let modules = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            Some((index, line.strip_prefix("pub mod ")?.strip_suffix(';')?))
+        })
+        .collect::<Vec<_>>();
+    let (last, _) = modules.last()?;
+    let mut index = modules
+        .iter()
+        .find(|(_, name)| *name > module.as_str())
+        .map_or(last + 1, |(index, _)| *index);
+    // Don't separate a module from its attributes.
+    while index > 0 && lines[index - 1].starts_with("#[") {
+        index -= 1;
+    }
+    let declaration = format!("pub mod {module};");
+    lines.insert(index, &declaration);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Import the module of the day in `solution.rs`, and add it to the
+/// solutions in order of the days.
+fn register_solution(solution: &str, day: u8) -> Option<String> {
+    let module = format!("day{day}");
+
+    // The days are imported together on a line of their own.
+    let mut lines = solution.lines().map(String::from).collect::<Vec<_>>();
+    let imports = lines.iter_mut().find(|line| {
+        let names = line.trim().trim_end_matches(',');
+        !names.is_empty() && names.split(", ").all(is_day_module)
+    })?;
+    let mut names = imports
+        .trim()
+        .trim_end_matches(',')
+        .split(", ")
+        .map(String::from)
+        .collect::<Vec<_>>();
+    names.push(module.clone());
+    names.sort();
+    *imports = format!("    {},", names.join(", "));
+    let solution = lines.join("\n") + "\n";
+
+    let start = solution.find("pub const SOLUTIONS")?;
+    let end = start + solution[start..].find("\n];")? + 1;
+    let entry_start = "    RegisteredSolution {\n";
+    // Before the first day that comes after it, or at the end.
+    let position = solution[start..end]
+        .match_indices(entry_start)
+        .map(|(offset, _)| start + offset)
+        .find(|&entry| {
+            solution[entry + entry_start.len()..]
+                .trim_start()
+                .strip_prefix("day: ")
+                .and_then(|rest| rest.split(',').next()?.parse::<u8>().ok())
+                .is_some_and(|entry_day| entry_day > day)
+        })
+        .unwrap_or(end);
+    let entry = format!(
+        "    RegisteredSolution {{
+        day: {day},
+        run: run::<{module}::Day{day}>,
+        bench: bench::measure::<{module}::Day{day}>,
+        generator: Generator {{
+            generate: {module}::generate,
+            real_size: 100,
+        }},
+        visualize: None,
+        draw: None,
+        stream: None,
+    }},
+"
+    );
+    Some(format!(
+        "{}{entry}{}",
+        &solution[..position],
+        &solution[position..]
+    ))
+}
+
+/// Add a new day to the repository at `root`: its module, registered with
+/// the library and the solutions, a binary of its own, an empty example with
+/// an entry for its answers, and a fuzz target if there are fuzz targets.
+///
+/// Returns the files that were created or changed. Nothing is written unless
+/// every file could be prepared.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|error| ScaffoldError::Unrecognized {
+            path: path.to_path_buf(),
+            reason: error.to_string(),
+        })
+    };
+    let unrecognized = |path: &Path, reason: &str| ScaffoldError::Unrecognized {
+        path: path.to_path_buf(),
+        reason: reason.to_string(),
+    };
+
+    let module_path = root.join("src").join(format!("day{day}.rs"));
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = read(&lib_path)?;
+    if module_path.exists() || lib.contains(&format!("pub mod day{day};")) {
+        return Err(ScaffoldError::Exists(day));
+    }
+
+    let mut files = vec![
+        (module_path, module_source(day)),
+        (
+            root.join("src").join("bin").join(format!("day{day}.rs")),
+            binary_source(day),
+        ),
+    ];
+    files.push((
+        lib_path.clone(),
+        register_module(&lib, day).ok_or_else(|| unrecognized(&lib_path, "no modules found."))?,
+    ));
+    let solution_path = root.join("src").join("solution.rs");
+    files.push((
+        solution_path.clone(),
+        register_solution(&read(&solution_path)?, day)
+            .ok_or_else(|| unrecognized(&solution_path, "no list of solutions found."))?,
+    ));
+
+    let example_path = root
+        .join("data")
+        .join(format!("day{day}"))
+        .join("example.txt");
+    if !example_path.exists() {
+        files.push((example_path, String::new()));
+    }
+    let answers_path = root.join(DEFAULT_ANSWERS_FILE);
+    let answers = read(&answers_path)?;
+    let header = format!("[day{day}.example]");
+    if !answers.lines().any(|line| line.trim() == header) {
+        files.push((
+            answers_path,
+            format!("{}\n\n{header}\n", answers.trim_end()),
+        ));
+    }
+
+    let fuzz = root.join("fuzz");
+    let fuzz_manifest_path = fuzz.join("Cargo.toml");
+    if fuzz_manifest_path.exists() {
+        let manifest = read(&fuzz_manifest_path)?;
+        files.push((
+            fuzz_manifest_path,
+            format!(
+                "{}\n\n[[bin]]\nname = \"day{day}\"\npath = \"fuzz_targets/day{day}.rs\"\ntest = false\ndoc = false\nbench = false\n",
+                manifest.trim_end()
+            ),
+        ));
+        files.push((
+            fuzz.join("fuzz_targets").join(format!("day{day}.rs")),
+            fuzz_target_source(day),
+        ));
+        files.push((
+            fuzz.join("corpus")
+                .join(format!("day{day}"))
+                .join("example.txt"),
+            String::new(),
+        ));
+    }
+
+    for (path, content) in &files {
+        let io_error = |error| ScaffoldError::Io {
+            path: path.clone(),
+            error,
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(io_error)?;
+        }
+        fs::write(path, content).map_err(io_error)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution;
+    use std::env;
+
+    #[test]
+    fn scaffold_day() {
+        let root = env::temp_dir().join(format!("aoc2022-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for file in [
+            "src/lib.rs",
+            "src/solution.rs",
+            "answers.toml",
+            "fuzz/Cargo.toml",
+        ] {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::copy(source.join(file), root.join(file)).unwrap();
+        }
+
+        // Add the last day that isn't there yet, and then the first one, to
+        // see that days are kept in order.
+        let free = (1..=25)
+            .filter(|day| solution::solution(*day).is_none())
+            .collect::<Vec<_>>();
+        let (first, last) = (free[0], free[free.len() - 1]);
+        let files = scaffold(&root, last).unwrap();
+        assert_eq!(files.len(), 9);
+        assert!(root.join(format!("data/day{last}/example.txt")).exists());
+        assert!(root.join(format!("src/bin/day{last}.rs")).exists());
+        assert!(root
+            .join(format!("fuzz/fuzz_targets/day{last}.rs"))
+            .exists());
+        scaffold(&root, first).unwrap();
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        let modules = lib
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub mod ")?.strip_suffix(';'))
+            .collect::<Vec<_>>();
+        assert!(modules.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(modules.contains(&format!("day{first}").as_str()));
+        let solution = fs::read_to_string(root.join("src/solution.rs")).unwrap();
+        assert!(solution.contains(&format!(" day{first}, ")));
+        let days = solution
+            .lines()
+            .filter_map(|line| {
+                line.trim()
+                    .strip_prefix("day: ")?
+                    .trim_end_matches(',')
+                    .parse()
+                    .ok()
+            })
+            .collect::<Vec<u8>>();
+        assert_eq!(days, (1..=first).chain([last]).collect::<Vec<_>>());
+        let answers = fs::read_to_string(root.join("answers.toml")).unwrap();
+        assert!(answers.ends_with(&format!(
+            "\n\n[day{last}.example]\n\n[day{first}.example]\n"
+        )));
+
+        assert!(matches!(scaffold(&root, first), Err(ScaffoldError::Exists(day)) if day == first));
+        assert!(matches!(
+            scaffold(&root, 26),
+            Err(ScaffoldError::InvalidDay(26))
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
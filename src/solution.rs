@@ -0,0 +1,598 @@
+use std::{
+    env,
+    error::Error,
+    fmt::Display,
+    hint::black_box,
+    io::{self, BufRead},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    bench::{self, Phase, PhaseTimings, Timing},
+    day1, day10, day2, day3, day4, day5, day6, day7, day8, day9,
+    generate::Generator,
+    image::Drawer,
+    input::{self, input_hash, Input},
+    parallel,
+    parsing::{Line, LineReader},
+    visualize::Visualizer,
+    ParseError,
+};
+
+/// The answer to one part of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    /// Answers that aren't numbers, like the crates on top of the stacks or
+    /// the letters drawn on a screen. These can span multiple lines.
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(number: i32) -> Self {
+        Answer::Number(number.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(number: i64) -> Self {
+        Answer::Number(number)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(number: u32) -> Self {
+        Answer::Number(number.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(number: usize) -> Self {
+        Answer::Number(number as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&Answer> for Value {
+    fn from(answer: &Answer) -> Self {
+        match answer {
+            Answer::Number(number) => (*number).into(),
+            Answer::Text(text) => text.as_str().into(),
+        }
+    }
+}
+
+/// The answer to one part of a day, or the reason it couldn't be computed
+/// from input that was parsed successfully.
+pub type PartResult = Result<Answer, Box<dyn Error + Send + Sync>>;
+
+/// The solution for a single day.
+///
+/// The input is parsed once and then handed to both parts, so the parts don't
+/// have to redo the parsing. The parsed input can borrow from the input text,
+/// so names and the like don't have to be copied out of it.
+pub trait Solution {
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError>;
+
+    fn part1(input: &Self::Input<'_>) -> PartResult;
+
+    fn part2(input: &Self::Input<'_>) -> PartResult;
+}
+
+/// A solution that can also be solved a line at a time, keeping only what it
+/// needs of the lines read so far, so the input never has to be in memory
+/// all at once.
+pub trait Streaming {
+    /// What is kept of the lines read so far.
+    type State: Default;
+
+    fn read_line(state: &mut Self::State, line: Line) -> Result<(), ParseError>;
+
+    /// Check the state once every line is read, for inputs that are wrong
+    /// as a whole, like empty ones.
+    fn finish(_state: &mut Self::State) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn answer(state: &Self::State, part: Part) -> PartResult;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<&str> for Part {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("{value} isn't a valid part, expected 1 or 2.")),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// What happened when running a day on some input.
+pub struct DayReport {
+    pub day: u8,
+    /// The hash of the input, see [`input_hash`].
+    pub input_hash: u64,
+    pub parse_time: Duration,
+    /// The answers for the parts that were asked for, in order, with how long
+    /// they took. Empty if the input couldn't be parsed.
+    pub parts: Vec<(Part, PartResult, Duration)>,
+    pub parse_error: Option<ParseError>,
+}
+
+impl DayReport {
+    pub fn is_success(&self) -> bool {
+        self.parse_error.is_none() && self.parts.iter().all(|(_, answer, _)| answer.is_ok())
+    }
+
+    pub fn to_json(&self) -> Value {
+        let nanoseconds =
+            |duration: Duration| -> i64 { duration.as_nanos().try_into().unwrap_or(i64::MAX) };
+        let mut json = json!({
+            "day": self.day,
+            "input_hash": format!("{:016x}", self.input_hash),
+            "parse_ns": nanoseconds(self.parse_time),
+        });
+        if let Some(error) = &self.parse_error {
+            json["parse_error"] = json!({
+                "line": error.line,
+                "column": error.column,
+                "expected": error.expected,
+                "found": error.text,
+            });
+        }
+        let parts = self
+            .parts
+            .iter()
+            .map(|(part, answer, time)| {
+                let mut json = json!({ "part": part.number() });
+                match answer {
+                    Ok(answer) => json["answer"] = answer.into(),
+                    Err(error) => json["error"] = error.to_string().into(),
+                }
+                json["time_ns"] = nanoseconds(*time).into();
+                json
+            })
+            .collect::<Vec<_>>();
+        json["parts"] = parts.into();
+        json
+    }
+}
+
+impl Display for DayReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Day {}", self.day)?;
+        if let Some(error) = &self.parse_error {
+            writeln!(f, "  Parse error: {error}")?;
+        }
+        for (part, answer, _) in &self.parts {
+            match answer {
+                Ok(Answer::Text(text)) if text.contains('\n') => {
+                    writeln!(f, "  Part {part}:")?;
+                    for line in text.lines() {
+                        writeln!(f, "    {line}")?;
+                    }
+                }
+                Ok(answer) => writeln!(f, "  Part {part}: {answer}")?,
+                Err(error) => writeln!(f, "  Part {part}: error: {error}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the input and run the requested parts of a solution.
+pub fn run<S: Solution>(day: u8, input: &str, parts: &[Part]) -> DayReport {
+    let input_hash = input_hash(input);
+    let start = Instant::now();
+    let parsed = S::parse(input);
+    let parse_time = start.elapsed();
+    let input = match parsed {
+        Ok(input) => input,
+        Err(error) => {
+            return DayReport {
+                day,
+                input_hash,
+                parse_time,
+                parts: Vec::new(),
+                parse_error: Some(error),
+            }
+        }
+    };
+    let parts = parts
+        .iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::part1(&input),
+                Part::Two => S::part2(&input),
+            };
+            (*part, answer, start.elapsed())
+        })
+        .collect();
+    DayReport {
+        day,
+        input_hash,
+        parse_time,
+        parts,
+        parse_error: None,
+    }
+}
+
+/// Read every line into the state of a streaming solution.
+///
+/// Returns the hash of the input along with the state, which is an error if
+/// the input couldn't be parsed. The input is read to the end either way, so
+/// the hash is always of all of it.
+fn read_stream<S: Streaming>(
+    reader: &mut dyn BufRead,
+) -> io::Result<(u64, Result<S::State, ParseError>)> {
+    let mut lines = LineReader::new(reader);
+    let mut state = Ok(S::State::default());
+    while let Some(line) = lines.next_line()? {
+        if let Ok(current) = &mut state {
+            if let Err(error) = S::read_line(current, line) {
+                state = Err(error);
+            }
+        }
+    }
+    let state = state.and_then(|mut state| S::finish(&mut state).map(|_| state));
+    Ok((lines.hash(), state))
+}
+
+/// Run the requested parts of a streaming solution, reading the input a line
+/// at a time. The time it takes to read the input counts as parsing.
+pub fn stream<S: Streaming>(
+    day: u8,
+    reader: &mut dyn BufRead,
+    parts: &[Part],
+) -> io::Result<DayReport> {
+    let start = Instant::now();
+    let (input_hash, state) = read_stream::<S>(reader)?;
+    let parse_time = start.elapsed();
+    let state = match state {
+        Ok(state) => state,
+        Err(error) => {
+            return Ok(DayReport {
+                day,
+                input_hash,
+                parse_time,
+                parts: Vec::new(),
+                parse_error: Some(error),
+            })
+        }
+    };
+    let parts = parts
+        .iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = S::answer(&state, *part);
+            (*part, answer, start.elapsed())
+        })
+        .collect();
+    Ok(DayReport {
+        day,
+        input_hash,
+        parse_time,
+        parts,
+        parse_error: None,
+    })
+}
+
+/// Time reading an input a line at a time into the state of a streaming
+/// solution, see [`bench::measure`].
+pub fn measure_stream<S: Streaming>(input: &str, iterations: u32) -> Result<Timing, ParseError> {
+    read_stream::<S>(&mut input.as_bytes())
+        .expect("Reading from memory can't fail.")
+        .1?;
+    Ok(bench::time(iterations, || {
+        read_stream::<S>(&mut black_box(input).as_bytes())
+    }))
+}
+
+/// How to solve a day a line at a time, for days that can.
+pub struct Streamer {
+    pub run: fn(u8, &mut dyn BufRead, &[Part]) -> io::Result<DayReport>,
+    pub bench: fn(&str, u32) -> Result<Timing, ParseError>,
+}
+
+/// A solution with its input type erased, so the solutions for all the days
+/// can be kept in one list.
+pub struct RegisteredSolution {
+    pub day: u8,
+    pub run: fn(u8, &str, &[Part]) -> DayReport,
+    pub bench: fn(&str, u32) -> Result<PhaseTimings, ParseError>,
+    pub generator: Generator,
+    /// Makes the simulation of days that have one, to watch it play out.
+    pub visualize: Option<Visualizer>,
+    /// Makes the pictures of days that have them.
+    pub draw: Option<Drawer>,
+    /// Solves the days that can be solved a line at a time.
+    pub stream: Option<Streamer>,
+}
+
+impl RegisteredSolution {
+    pub fn run(&self, input: &str, parts: &[Part]) -> DayReport {
+        (self.run)(self.day, input, parts)
+    }
+
+    /// Run the requested parts a line at a time, or `None` if the day can't
+    /// be solved like that.
+    pub fn stream(
+        &self,
+        reader: &mut dyn BufRead,
+        parts: &[Part],
+    ) -> Option<io::Result<DayReport>> {
+        Some((self.stream.as_ref()?.run)(self.day, reader, parts))
+    }
+
+    /// Time the parser and both parts, running each phase `iterations` times,
+    /// and reading the input a line at a time if the day can be solved like
+    /// that.
+    pub fn bench(&self, input: &str, iterations: u32) -> Result<PhaseTimings, ParseError> {
+        let mut timings = (self.bench)(input, iterations)?;
+        if let Some(stream) = &self.stream {
+            timings.push((Phase::Stream, (stream.bench)(input, iterations)?));
+        }
+        Ok(timings)
+    }
+}
+
+pub const SOLUTIONS: &[RegisteredSolution] = &[
+    RegisteredSolution {
+        day: 1,
+        run: run::<day1::Day1>,
+        bench: bench::measure::<day1::Day1>,
+        generator: Generator {
+            generate: day1::generate,
+            real_size: 250,
+        },
+        visualize: None,
+        draw: None,
+        stream: Some(Streamer {
+            run: stream::<day1::Day1>,
+            bench: measure_stream::<day1::Day1>,
+        }),
+    },
+    RegisteredSolution {
+        day: 2,
+        run: run::<day2::Day2>,
+        bench: bench::measure::<day2::Day2>,
+        generator: Generator {
+            generate: day2::generate,
+            real_size: 2500,
+        },
+        visualize: None,
+        draw: None,
+        stream: None,
+    },
+    RegisteredSolution {
+        day: 3,
+        run: run::<day3::Day3>,
+        bench: bench::measure::<day3::Day3>,
+        generator: Generator {
+            generate: day3::generate,
+            real_size: 300,
+        },
+        visualize: None,
+        draw: None,
+        stream: None,
+    },
+    RegisteredSolution {
+        day: 4,
+        run: run::<day4::Day4>,
+        bench: bench::measure::<day4::Day4>,
+        generator: Generator {
+            generate: day4::generate,
+            real_size: 1000,
+        },
+        visualize: None,
+        draw: None,
+        stream: Some(Streamer {
+            run: stream::<day4::Day4>,
+            bench: measure_stream::<day4::Day4>,
+        }),
+    },
+    RegisteredSolution {
+        day: 5,
+        run: run::<day5::Day5>,
+        bench: bench::measure::<day5::Day5>,
+        generator: Generator {
+            generate: day5::generate,
+            real_size: 500,
+        },
+        visualize: Some(day5::visualize),
+        draw: None,
+        stream: None,
+    },
+    RegisteredSolution {
+        day: 6,
+        run: run::<day6::Day6>,
+        bench: bench::measure::<day6::Day6>,
+        generator: Generator {
+            generate: day6::generate,
+            real_size: 4096,
+        },
+        visualize: None,
+        draw: None,
+        stream: None,
+    },
+    RegisteredSolution {
+        day: 7,
+        run: run::<day7::Day7>,
+        bench: bench::measure::<day7::Day7>,
+        generator: Generator {
+            generate: day7::generate,
+            real_size: 700,
+        },
+        visualize: Some(day7::visualize),
+        draw: None,
+        stream: None,
+    },
+    RegisteredSolution {
+        day: 8,
+        run: run::<day8::Day8>,
+        bench: bench::measure::<day8::Day8>,
+        generator: Generator {
+            generate: day8::generate,
+            real_size: 99,
+        },
+        visualize: Some(day8::visualize),
+        draw: Some(day8::draw),
+        stream: None,
+    },
+    RegisteredSolution {
+        day: 9,
+        run: run::<day9::Day9>,
+        bench: bench::measure::<day9::Day9>,
+        generator: Generator {
+            generate: day9::generate,
+            real_size: 2000,
+        },
+        visualize: Some(day9::visualize),
+        draw: Some(day9::draw),
+        stream: Some(Streamer {
+            run: stream::<day9::Day9>,
+            bench: measure_stream::<day9::Day9>,
+        }),
+    },
+    RegisteredSolution {
+        day: 10,
+        run: run::<day10::Day10>,
+        bench: bench::measure::<day10::Day10>,
+        generator: Generator {
+            generate: day10::generate,
+            real_size: 140,
+        },
+        visualize: Some(day10::visualize),
+        draw: Some(day10::draw),
+        stream: Some(Streamer {
+            run: stream::<day10::Day10>,
+            bench: measure_stream::<day10::Day10>,
+        }),
+    },
+];
+
+/// The registered solution for a day, if that day has been solved.
+pub fn solution(day: u8) -> Option<&'static RegisteredSolution> {
+    SOLUTIONS.iter().find(|solution| solution.day == day)
+}
+
+/// Solve both parts of a day and print the answers, for the `dayN` binaries.
+/// The input is the file given as the only argument, or stdin for `-`, and
+/// the `real` dataset without one.
+pub fn solve_day(day: u8) -> ExitCode {
+    let Some(solution) = solution(day) else {
+        eprintln!("Day {day} isn't solved.");
+        return ExitCode::FAILURE;
+    };
+    let input = env::args()
+        .nth(1)
+        .map_or_else(Input::default, |argument| Input::from_argument(&argument));
+    let input = match input.read(day, &input::data_directory(None)) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match parallel::catch_panic(|| solution.run(&input, &Part::ALL)) {
+        Ok(report) => {
+            print!("{report}");
+            if report.is_success() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(message) => {
+            eprintln!("Day {day} panicked: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    /// The answers or the error of a report, to compare reports by.
+    fn outcome(report: &DayReport) -> Result<Vec<Result<Answer, String>>, ParseError> {
+        match &report.parse_error {
+            Some(error) => Err(error.clone()),
+            None => Ok(report
+                .parts
+                .iter()
+                .map(|(_, answer, _)| answer.as_ref().cloned().map_err(|error| error.to_string()))
+                .collect()),
+        }
+    }
+
+    #[test]
+    fn streaming_matches_parsing() {
+        let mut rng = Rng::new(25);
+        for solution in SOLUTIONS
+            .iter()
+            .filter(|solution| solution.stream.is_some())
+        {
+            let generated = (solution.generator.generate)(&mut rng, 200);
+            let broken = format!("{generated}oops\n");
+            for input in ["", generated.as_str(), broken.as_str()] {
+                let parsed = solution.run(input, &Part::ALL);
+                let streamed = solution
+                    .stream(&mut input.as_bytes(), &Part::ALL)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(outcome(&streamed), outcome(&parsed), "day {}", solution.day);
+                assert_eq!(streamed.input_hash, parsed.input_hash);
+            }
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Submitting answers, while keeping a history of every attempt so answers
+//! that are known to be wrong are never sent twice.
+
+use std::{
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    client::{Client, FetchError, LazyClient},
+    Answer, Part,
+};
+
+/// The history file used when none is given.
+pub const DEFAULT_HISTORY_FILE: &str = "submissions.jsonl";
+
+/// How long the website makes you wait after a wrong answer when it doesn't
+/// say.
+const WRONG_ANSWER_WAIT: Duration = Duration::from_secs(60);
+
+/// Something that can submit an answer and return the page it got back.
+pub trait Submitter {
+    fn submit(&self, day: u8, part: Part, answer: &str) -> Result<String, FetchError>;
+}
+
+impl Submitter for Client {
+    fn submit(&self, day: u8, part: Part, answer: &str) -> Result<String, FetchError> {
+        self.post(
+            &format!("/day/{day}/answer"),
+            &[("level", &part.to_string()), ("answer", answer)],
+        )
+    }
+}
+
+impl Submitter for LazyClient {
+    fn submit(&self, day: u8, part: Part, answer: &str) -> Result<String, FetchError> {
+        self.client()?.submit(day, part, answer)
+    }
+}
+
+/// What the website said about an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// The answer wasn't checked, because the last one was too recent.
+    RateLimited,
+    /// The part was already solved, so the answer wasn't checked.
+    AlreadySolved,
+    /// A page that isn't understood, with its text.
+    Unknown(String),
+}
+
+impl Verdict {
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Right => "right",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::RateLimited => "rate_limited",
+            Verdict::AlreadySolved => "already_solved",
+            Verdict::Unknown(_) => "unknown",
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "That's the right answer!"),
+            Verdict::Wrong => write!(f, "That's not the right answer."),
+            Verdict::TooHigh => write!(f, "That's not the right answer, it's too high."),
+            Verdict::TooLow => write!(f, "That's not the right answer, it's too low."),
+            Verdict::RateLimited => write!(f, "An answer was given too recently."),
+            Verdict::AlreadySolved => write!(f, "This part is already solved."),
+            Verdict::Unknown(text) => write!(f, "Unexpected response: {text}"),
+        }
+    }
+}
+
+/// The verdict on the page the website returned for an answer, and how long
+/// it asks to wait before the next one.
+pub fn parse_response(page: &str) -> (Verdict, Option<Duration>) {
+    let text = article_text(page);
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Right
+    } else if text.contains("answer too recently") {
+        Verdict::RateLimited
+    } else if text.contains("solving the right level") {
+        Verdict::AlreadySolved
+    } else if text.contains("not the right answer") {
+        if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else {
+        Verdict::Unknown(text.clone())
+    };
+    (verdict, parse_wait(&text))
+}
+
+/// The text of the `<article>` of a page, or of the whole page if it has
+/// none, without tags.
+fn article_text(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for char in article.chars() {
+        match char {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            char if !in_tag => text.push(char),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// How long a page asks to wait, from either "you have 1m 30s left to wait"
+/// or "please wait 5 minutes before trying again".
+fn parse_wait(text: &str) -> Option<Duration> {
+    let text = text.to_lowercase();
+    if let Some((before, _)) = text.split_once(" left to wait") {
+        let (_, amount) = before.rsplit_once("you have ")?;
+        return amount
+            .split_whitespace()
+            .map(|amount| {
+                let (number, unit) =
+                    amount.split_at(amount.find(|char: char| !char.is_ascii_digit())?);
+                let number = number.parse::<u64>().ok()?;
+                match unit {
+                    "h" => Some(number * 60 * 60),
+                    "m" => Some(number * 60),
+                    "s" => Some(number),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+
+    let (_, rest) = text.split_once("please wait ")?;
+    let mut words = rest.split_whitespace();
+    let number = match words.next()? {
+        "one" | "a" => 1,
+        number => number.parse().ok()?,
+    };
+    match words.next()? {
+        "minute" | "minutes" => Some(Duration::from_secs(number * 60)),
+        "second" | "seconds" => Some(Duration::from_secs(number)),
+        _ => None,
+    }
+}
+
+/// A single submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// When it was submitted, in seconds since the Unix epoch.
+    pub time: u64,
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub verdict: Verdict,
+    /// How long the website asked to wait before the next answer.
+    pub wait: Option<Duration>,
+}
+
+impl Attempt {
+    fn to_json(&self) -> Value {
+        let mut json = json!({
+            "time": self.time,
+            "day": self.day,
+            "part": self.part.to_string(),
+            "answer": Value::from(&self.answer),
+            "verdict": self.verdict.name(),
+        });
+        if let Verdict::Unknown(text) = &self.verdict {
+            json["message"] = text.as_str().into();
+        }
+        if let Some(wait) = self.wait {
+            json["wait_s"] = wait.as_secs().into();
+        }
+        json
+    }
+
+    fn from_json(json: &Value) -> Option<Self> {
+        let number = |name| json.get(name).and_then(Value::as_i64);
+        let verdict = match json.get("verdict")?.as_str()? {
+            "right" => Verdict::Right,
+            "wrong" => Verdict::Wrong,
+            "too_high" => Verdict::TooHigh,
+            "too_low" => Verdict::TooLow,
+            "rate_limited" => Verdict::RateLimited,
+            "already_solved" => Verdict::AlreadySolved,
+            "unknown" => Verdict::Unknown(json.get("message")?.as_str()?.to_string()),
+            _ => return None,
+        };
+        Some(Self {
+            time: number("time")?.try_into().ok()?,
+            day: number("day")?.try_into().ok()?,
+            part: Part::try_from(json.get("part")?.as_str()?).ok()?,
+            answer: match json.get("answer")? {
+                Value::Number(number) => Answer::Number(number.as_i64()?),
+                Value::String(text) => Answer::Text(text.clone()),
+                _ => return None,
+            },
+            verdict,
+            wait: match number("wait_s") {
+                Some(wait) => Some(Duration::from_secs(wait.try_into().ok()?)),
+                None => None,
+            },
+        })
+    }
+}
+
+/// Every attempt so far, stored one JSON object per line so new attempts can
+/// be appended.
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    /// Load the history, which is empty if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(format!("Failed to read {}: {error}", path.display())),
+        };
+        let attempts = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let json = serde_json::from_str(line).map_err(|error| {
+                    format!(
+                        "Invalid history in {}: line {}: {error}",
+                        path.display(),
+                        index + 1
+                    )
+                })?;
+                Attempt::from_json(&json).ok_or_else(|| {
+                    format!(
+                        "Invalid history in {}: line {} isn't an attempt",
+                        path.display(),
+                        index + 1
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            attempts,
+        })
+    }
+
+    /// Add an attempt, writing it to the file straight away.
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), String> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", attempt.to_json()))
+            .map_err(|error| format!("Failed to write {}: {error}", self.path.display()))?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    /// The reason an answer shouldn't be submitted at `now`, if there is one.
+    pub fn refusal(&self, day: u8, part: Part, answer: &Answer, now: u64) -> Option<String> {
+        if let Some(wait_until) = self
+            .attempts
+            .last()
+            .and_then(|attempt| {
+                let wait = attempt
+                    .wait
+                    .or_else(|| attempt.verdict.is_wrong().then_some(WRONG_ANSWER_WAIT))?;
+                Some(attempt.time + wait.as_secs())
+            })
+            .filter(|wait_until| *wait_until > now)
+        {
+            return Some(format!(
+                "The website asked to wait, try again in {}s.",
+                wait_until - now
+            ));
+        }
+
+        let attempts = self
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.day == day && attempt.part == part);
+        for attempt in attempts {
+            match (&attempt.verdict, &attempt.answer, answer) {
+                (Verdict::Right, right, _) => {
+                    return Some(format!("Day {day} part {part} is already solved: {right}."))
+                }
+                (verdict, wrong, answer) if verdict.is_wrong() && wrong == answer => {
+                    return Some(format!("{answer} was already rejected."))
+                }
+                (Verdict::TooLow, Answer::Number(low), Answer::Number(answer)) if answer < low => {
+                    return Some(format!("{answer} is below {low}, which was too low."))
+                }
+                (Verdict::TooHigh, Answer::Number(high), Answer::Number(answer))
+                    if answer > high =>
+                {
+                    return Some(format!("{answer} is above {high}, which was too high."))
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Why an answer wasn't submitted.
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The history says the answer can't be right, or that it's too soon.
+    Refused(String),
+    Fetch(FetchError),
+    History(String),
+}
+
+impl Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::Refused(reason) => write!(f, "Not submitting: {reason}"),
+            SubmitError::Fetch(error) => write!(f, "{error}"),
+            SubmitError::History(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Submit an answer unless the history rules it out, and record the attempt.
+pub fn submit(
+    submitter: &dyn Submitter,
+    history: &mut History,
+    day: u8,
+    part: Part,
+    answer: &Answer,
+) -> Result<Verdict, SubmitError> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    if let Some(reason) = history.refusal(day, part, answer, now) {
+        return Err(SubmitError::Refused(reason));
+    }
+    if matches!(answer, Answer::Text(text) if text.contains('\n')) {
+        return Err(SubmitError::Refused(
+            "The answer spans multiple lines, read it and pass it with --answer.".to_string(),
+        ));
+    }
+
+    let page = submitter
+        .submit(day, part, &answer.to_string())
+        .map_err(SubmitError::Fetch)?;
+    let (verdict, wait) = parse_response(&page);
+    history
+        .record(Attempt {
+            time: now,
+            day,
+            part,
+            answer: answer.clone(),
+            verdict: verdict.clone(),
+            wait,
+        })
+        .map_err(SubmitError::History)?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::server;
+    use std::env;
+
+    const TOO_LOW: &str = "<main><article><p>That's not the right answer; your answer is too low. \
+        If you're stuck, make sure you're using the full input data. Please wait one minute \
+        before trying again. <a href=\"/2022/day/1\">[Return to Day 1]</a></p></article></main>";
+
+    #[test]
+    fn responses() {
+        assert_eq!(
+            parse_response(TOO_LOW),
+            (Verdict::TooLow, Some(Duration::from_secs(60)))
+        );
+        assert_eq!(
+            parse_response(
+                "<article><p>You gave an answer too recently; you have to wait after \
+                 submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>"
+            ),
+            (Verdict::RateLimited, Some(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            parse_response(
+                "<article><p>That's the right answer!  You are <em>one gold star</em> closer.</p></article>"
+            ),
+            (Verdict::Right, None)
+        );
+        assert_eq!(
+            parse_response(
+                "<article><p>You don't seem to be solving the right level.</p></article>"
+            ),
+            (Verdict::AlreadySolved, None)
+        );
+    }
+
+    #[test]
+    fn submit_and_refuse() {
+        let (url, requests) = server::start(vec![(200, TOO_LOW)]);
+        let client = Client::new(&url, "secret");
+        let path = env::temp_dir().join(format!("aoc2022-history-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut history = History::load(&path).unwrap();
+
+        let verdict = submit(&client, &mut history, 1, Part::One, &Answer::Number(100));
+        assert_eq!(verdict.unwrap(), Verdict::TooLow);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "POST /2022/day/1/answer HTTP/1.1");
+        assert_eq!(request.body, "level=1&answer=100");
+
+        // The server only answers once, so these have to be refused locally.
+        let mut history = History::load(&path).unwrap();
+        assert_eq!(history.attempts.len(), 1);
+        history.attempts[0].time -= 120;
+        for answer in [100, 99] {
+            assert!(matches!(
+                submit(&client, &mut history, 1, Part::One, &Answer::Number(answer)),
+                Err(SubmitError::Refused(_))
+            ));
+        }
+        // Too soon after the wrong answer.
+        let now = history.attempts[0].time + 30;
+        assert!(history
+            .refusal(1, Part::One, &Answer::Number(101), now)
+            .is_some());
+        assert!(history
+            .refusal(1, Part::One, &Answer::Number(101), now + 60)
+            .is_none());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+use std::{fmt::Display, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::{
+    answers::Answers,
+    input::Input,
+    parallel,
+    solution::{self, Answer},
+    Part,
+};
+
+/// How the answer of a solver compares to the known answer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: Answer,
+        actual: Answer,
+    },
+    /// The solver couldn't produce an answer at all.
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Verification {
+    pub day: u8,
+    pub dataset: String,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+impl Verification {
+    pub fn to_json(&self) -> Value {
+        let mut json = json!({
+            "day": self.day,
+            "dataset": self.dataset,
+            "part": self.part.number(),
+        });
+        match &self.outcome {
+            Outcome::Pass => json["outcome"] = "pass".into(),
+            Outcome::Fail { expected, actual } => {
+                json["outcome"] = "fail".into();
+                json["expected"] = expected.into();
+                json["actual"] = actual.into();
+            }
+            Outcome::Error(error) => {
+                json["outcome"] = "error".into();
+                json["error"] = error.as_str().into();
+            }
+        }
+        json
+    }
+}
+
+/// Run the solvers on every dataset that has known answers and compare their
+/// answers to the known ones.
+///
+/// Only the days and datasets for which `include` returns true are verified.
+pub fn verify(
+    answers: &Answers,
+    data_directory: &Path,
+    include: impl Fn(u8, &str) -> bool,
+) -> Vec<Verification> {
+    let mut verifications = Vec::new();
+
+    for ((day, dataset), entry) in &answers.0 {
+        if !include(*day, dataset) {
+            continue;
+        }
+        let parts = Part::ALL
+            .into_iter()
+            .filter(|part| entry.get(*part).is_some())
+            .collect::<Vec<_>>();
+        let mut verification = |part, outcome| {
+            verifications.push(Verification {
+                day: *day,
+                dataset: dataset.clone(),
+                part,
+                outcome,
+            })
+        };
+
+        let Some(solution) = solution::solution(*day) else {
+            for part in parts {
+                verification(part, Outcome::Error(format!("Day {day} isn't solved.")));
+            }
+            continue;
+        };
+        let input = match Input::Dataset(dataset.clone()).read(*day, data_directory) {
+            Ok(input) => input,
+            Err(error) => {
+                for part in parts {
+                    verification(part, Outcome::Error(error.to_string()));
+                }
+                continue;
+            }
+        };
+
+        let report = match parallel::catch_panic(|| solution.run(&input, &parts)) {
+            Ok(report) => report,
+            Err(message) => {
+                for part in parts {
+                    verification(part, Outcome::Error(format!("Panicked: {message}")));
+                }
+                continue;
+            }
+        };
+        if let Some(error) = report.parse_error {
+            for part in parts {
+                verification(part, Outcome::Error(format!("Parse error: {error}")));
+            }
+            continue;
+        }
+        for (part, answer, _) in report.parts {
+            let expected = entry.get(part).expect("Only parts with answers are run.");
+            let outcome = match answer {
+                Ok(answer) if answer == *expected => Outcome::Pass,
+                Ok(answer) => Outcome::Fail {
+                    expected: expected.clone(),
+                    actual: answer,
+                },
+                Err(error) => Outcome::Error(error.to_string()),
+            };
+            verification(part, outcome);
+        }
+    }
+
+    verifications
+}
+
+/// The lines of two answers, with the lines of the first that differ marked
+/// with `-` and the ones of the second with `+`.
+pub struct Diff<'a>(pub &'a Answer, pub &'a Answer);
+
+impl Display for Diff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let old = self.0.to_string();
+        let new = self.1.to_string();
+        let old_lines = old.lines().collect::<Vec<_>>();
+        let new_lines = new.lines().collect::<Vec<_>>();
+        for line in 0..old_lines.len().max(new_lines.len()) {
+            match (old_lines.get(line), new_lines.get(line)) {
+                (Some(old), Some(new)) if old == new => writeln!(f, "  {old}")?,
+                (old, new) => {
+                    if let Some(old) = old {
+                        writeln!(f, "- {old}")?;
+                    }
+                    if let Some(new) = new {
+                        writeln!(f, "+ {new}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The verifications as a table, with the difference between the expected and
+/// actual answer below every failure.
+pub struct VerificationTable<'a>(pub &'a [Verification]);
+
+impl Display for VerificationTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dataset_width = self
+            .0
+            .iter()
+            .map(|verification| verification.dataset.len())
+            .chain(["Dataset".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "Day  {:dataset_width$}  Part  Result", "Dataset")?;
+        for verification in self.0 {
+            write!(
+                f,
+                "{:>3}  {:dataset_width$}  {:>4}  ",
+                verification.day, verification.dataset, verification.part
+            )?;
+            match &verification.outcome {
+                Outcome::Pass => writeln!(f, "pass")?,
+                Outcome::Error(error) => writeln!(f, "ERROR {error}")?,
+                Outcome::Fail { expected, actual } => {
+                    writeln!(f, "FAIL")?;
+                    for line in Diff(expected, actual).to_string().lines() {
+                        writeln!(f, "     {line}")?;
+                    }
+                }
+            }
+        }
+        let passed = self
+            .0
+            .iter()
+            .filter(|verification| verification.outcome == Outcome::Pass)
+            .count();
+        write!(f, "{passed} passed, {} failed", self.0.len() - passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn stored_answers() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let answers = Answers::load(&root.join(crate::answers::DEFAULT_ANSWERS_FILE)).unwrap();
+
+        let verifications = verify(&answers, &root.join("data"), |_, dataset| {
+            // Real inputs aren't checked in.
+            dataset != "real"
+        });
+
+        assert!(!verifications.is_empty());
+        assert!(
+            verifications
+                .iter()
+                .all(|verification| verification.outcome == Outcome::Pass),
+            "{}",
+            VerificationTable(&verifications)
+        );
+    }
+
+    #[test]
+    fn failure_diff() {
+        let verifications = [Verification {
+            day: 10,
+            dataset: "example".to_string(),
+            part: Part::Two,
+            outcome: Outcome::Fail {
+                expected: Answer::Text("##..\n###.".to_string()),
+                actual: Answer::Text("##..\n#.#.".to_string()),
+            },
+        }];
+        assert_eq!(
+            VerificationTable(&verifications).to_string(),
+            "Day  Dataset  Part  Result
+ 10  example     2  FAIL
+       ##..
+     - ###.
+     + #.#.
+0 passed, 1 failed"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Watching the simulations of the days run, a frame at a time, in the
+//! terminal or exported to a text file.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    image::{Animation, Image},
+    ParseError,
+};
+
+/// The frames per second used when none are given.
+pub const DEFAULT_FPS: u32 = 10;
+
+/// A simulation that can be shown a step at a time.
+pub trait Visualize {
+    /// The current state, drawn as text.
+    fn frame(&self) -> String;
+
+    /// Move the simulation a step further, returning false if it was already
+    /// done.
+    fn step(&mut self) -> bool;
+
+    /// The most steps the simulation takes from the start.
+    fn steps(&self) -> usize;
+
+    /// The current state as a picture, for simulations that can be drawn.
+    fn image(&self) -> Option<Image> {
+        None
+    }
+}
+
+/// Makes the visualization of a day from its input, which it can borrow from.
+pub type Visualizer = fn(&str) -> Result<Box<dyn Visualize + '_>, ParseError>;
+
+/// Write every frame to a file, each below a line with its number.
+///
+/// Returns the amount of frames.
+pub fn export(visualization: &mut dyn Visualize, path: &Path) -> io::Result<usize> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    let mut frames = 0;
+    loop {
+        frames += 1;
+        writeln!(file, "--- Frame {frames} ---\n{}", visualization.frame())?;
+        if !visualization.step() {
+            break;
+        }
+    }
+    file.flush()?;
+    Ok(frames)
+}
+
+/// About the most frames an animation has, longer simulations skip steps.
+pub const MAX_ANIMATION_FRAMES: usize = 300;
+
+/// Save the frames as pictures in an animated PNG, playing `fps` frames a
+/// second, with every cell `scale` pixels wide.
+///
+/// Returns the amount of frames.
+pub fn export_animation(
+    visualization: &mut dyn Visualize,
+    path: &Path,
+    fps: u32,
+    scale: usize,
+) -> io::Result<usize> {
+    let unsupported =
+        || io::Error::new(io::ErrorKind::Unsupported, "this simulation can't be drawn");
+    // Skip steps evenly to keep the animation short enough.
+    let stride = visualization.steps().div_ceil(MAX_ANIMATION_FRAMES).max(1);
+    let mut animation = Animation::default();
+    let mut step = 0;
+    loop {
+        if step % stride == 0 {
+            animation.push(&visualization.image().ok_or_else(unsupported)?.scaled(scale));
+        }
+        if !visualization.step() {
+            // Always end on the final state.
+            if step % stride != 0 {
+                animation.push(&visualization.image().ok_or_else(unsupported)?.scaled(scale));
+            }
+            break;
+        }
+        step += 1;
+    }
+
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    animation.write(fps, &mut file)?;
+    file.flush()?;
+    Ok(animation.len())
+}
+
+/// Plays a simulation in the terminal, controlled by lines of input:
+///
+/// - an empty line pauses or resumes,
+/// - `n` goes a single step further while paused,
+/// - `+` and `-` double or halve the speed,
+/// - `q` stops.
+pub struct Player {
+    pub fps: u32,
+    pub paused: bool,
+}
+
+impl Player {
+    /// Play until the simulation is done or stopped, reading the controls
+    /// from `controls` and drawing on `output`.
+    pub fn play(
+        &mut self,
+        visualization: &mut dyn Visualize,
+        controls: &Receiver<String>,
+        output: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut frame = 1;
+        let mut done = false;
+        loop {
+            let state = match (done, self.paused) {
+                (true, _) => "done",
+                (false, true) => "paused",
+                (false, false) => "playing",
+            };
+            // Move to the top left and clear the screen before every frame.
+            write!(
+                output,
+                "\x1b[H\x1b[2J{}\nFrame {frame}, {} fps, {state}. Enter: pause, n: step, +/-: speed, q: quit\n",
+                visualization.frame(),
+                self.fps
+            )?;
+            output.flush()?;
+            if done {
+                return Ok(());
+            }
+
+            let delay = Duration::from_secs(1) / self.fps;
+            let control = if self.paused {
+                match controls.recv() {
+                    Ok(control) => Some(control),
+                    // Without controls there is no way to resume.
+                    Err(_) => return Ok(()),
+                }
+            } else {
+                match controls.recv_timeout(delay) {
+                    Ok(control) => Some(control),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(delay);
+                        None
+                    }
+                }
+            };
+
+            let step = match control.as_deref().map(str::trim) {
+                None => true,
+                Some("") => {
+                    self.paused = !self.paused;
+                    false
+                }
+                Some("n") => self.paused,
+                Some("+") => {
+                    self.fps = self.fps.saturating_mul(2).min(1000);
+                    false
+                }
+                Some("-") => {
+                    self.fps = (self.fps / 2).max(1);
+                    false
+                }
+                Some("q") => return Ok(()),
+                Some(_) => false,
+            };
+            if step {
+                if visualization.step() {
+                    frame += 1;
+                } else {
+                    done = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, sync::mpsc};
+
+    /// Counts up to a number.
+    struct Counter(u32, u32);
+
+    impl Visualize for Counter {
+        fn frame(&self) -> String {
+            format!("count {}", self.0)
+        }
+
+        fn step(&mut self) -> bool {
+            self.0 < self.1 && {
+                self.0 += 1;
+                true
+            }
+        }
+
+        fn steps(&self) -> usize {
+            self.1 as usize
+        }
+    }
+
+    #[test]
+    fn play_and_export() {
+        let (sender, controls) = mpsc::channel();
+        for control in ["n", "n", "+", "", "q"] {
+            sender.send(control.to_string()).unwrap();
+        }
+        let mut output = Vec::new();
+        let mut player = Player {
+            fps: 1,
+            paused: true,
+        };
+        player
+            .play(&mut Counter(0, 100), &controls, &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("count 2\nFrame 3, 1 fps, paused."));
+        assert!(output.contains("count 2\nFrame 3, 2 fps, playing."));
+        assert!(!output.contains("count 3"));
+
+        // Without controls it plays until the end.
+        drop(sender);
+        let mut output = Vec::new();
+        let mut player = Player {
+            fps: 1000,
+            paused: false,
+        };
+        player
+            .play(&mut Counter(0, 3), &controls, &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output).unwrap().ends_with(
+            "count 3\nFrame 4, 1000 fps, done. Enter: pause, n: step, +/-: speed, q: quit\n"
+        ));
+
+        let path = env::temp_dir().join(format!("aoc2022-frames-{}.txt", std::process::id()));
+        assert_eq!(export(&mut Counter(0, 2), &path).unwrap(), 3);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "--- Frame 1 ---\ncount 0\n--- Frame 2 ---\ncount 1\n--- Frame 3 ---\ncount 2\n"
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,441 @@
+//! Re-running a day whenever its source or its inputs change.
+//!
+//! The solutions are compiled into the `aoc` binary, so every run goes
+//! through `cargo`, which rebuilds whatever changed first.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    process::{self, Output},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use serde_json::Value;
+
+use crate::{answers::Answers, solution::Answer, verify::Diff, Part};
+
+/// How often the files are checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// When the watched files were last changed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, SystemTime>);
+
+impl Snapshot {
+    /// Look at the files, and at every file in the directories. Files that
+    /// don't exist are left out, so creating them counts as a change.
+    pub fn take(paths: &[PathBuf]) -> Self {
+        let mut files = BTreeMap::new();
+        for path in paths {
+            let entries = match fs::read_dir(path) {
+                Ok(entries) => entries
+                    .filter_map(|entry| Some(entry.ok()?.path()))
+                    .collect(),
+                Err(_) => vec![path.clone()],
+            };
+            for file in entries {
+                if let Ok(modified) = fs::metadata(&file).and_then(|metadata| metadata.modified()) {
+                    files.insert(file, modified);
+                }
+            }
+        }
+        Self(files)
+    }
+
+    /// The files that were added, removed or changed since the earlier
+    /// snapshot.
+    pub fn changes<'a>(&'a self, earlier: &'a Snapshot) -> Vec<&'a Path> {
+        let mut changes = self
+            .0
+            .iter()
+            .filter(|(path, modified)| earlier.0.get(*path) != Some(modified))
+            .map(|(path, _)| path.as_path())
+            .collect::<Vec<_>>();
+        changes.extend(
+            earlier
+                .0
+                .keys()
+                .filter(|path| !self.0.contains_key(*path))
+                .map(PathBuf::as_path),
+        );
+        changes
+    }
+}
+
+/// The answer of a part, or why there is none.
+pub type PartOutcome = Result<Answer, String>;
+
+/// The answers of a run of the `aoc` binary with `--format json`.
+pub fn answers_from_json(output: &str) -> Result<Vec<(Part, PartOutcome)>, String> {
+    let value = serde_json::from_str::<Value>(output)
+        .map_err(|error| format!("Unexpected output: {error}"))?;
+    let report = value
+        .as_array()
+        .and_then(|reports| reports.first())
+        .ok_or("Unexpected output: no report.")?;
+    if let Some(error) = report.get("error").and_then(Value::as_str) {
+        return Err(error.to_string());
+    }
+    if let Some(error) = report.get("parse_error") {
+        let field = |name| error.get(name).and_then(Value::as_i64).unwrap_or_default();
+        return Err(format!(
+            "Parse error at line {}, column {}: expected {}",
+            field("line"),
+            field("column"),
+            error.get("expected").and_then(Value::as_str).unwrap_or("?")
+        ));
+    }
+
+    let parts = report
+        .get("parts")
+        .and_then(Value::as_array)
+        .ok_or("Unexpected output: no parts.")?;
+    parts
+        .iter()
+        .map(|value| {
+            let number = value
+                .get("part")
+                .and_then(Value::as_i64)
+                .unwrap_or_default();
+            let part = Part::try_from(number.to_string().as_str())?;
+            let outcome = match (value.get("answer"), value.get("error")) {
+                (Some(Value::Number(number)), _) => number
+                    .as_i64()
+                    .map(Answer::Number)
+                    .ok_or_else(|| format!("Unexpected output: {number} isn't an answer.")),
+                (Some(Value::String(text)), _) => Ok(Answer::Text(text.clone())),
+                (_, Some(Value::String(error))) => Err(error.clone()),
+                _ => Err("Unexpected output: no answer.".to_string()),
+            };
+            Ok((part, outcome))
+        })
+        .collect()
+}
+
+/// The errors, with where they are, in the output of cargo.
+fn compiler_errors(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| {
+            (line.starts_with("error") && !line.starts_with("error: test failed"))
+                || line.trim_start().starts_with("--> ")
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// How the answer of a part compares to the one of the last run and to the
+/// known answer.
+pub struct PartChange<'a> {
+    pub part: Part,
+    pub current: &'a PartOutcome,
+    /// The answer of the last run, if there was one.
+    pub previous: Option<&'a PartOutcome>,
+    pub expected: Option<&'a Answer>,
+}
+
+impl Display for PartChange<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let answer = match self.current {
+            Ok(answer) => answer,
+            Err(error) => return writeln!(f, "Part {}: error: {error}", self.part),
+        };
+        let multiline = answer.to_string().contains('\n');
+        let previous = match self.previous {
+            Some(Ok(previous)) => Some(previous),
+            _ => None,
+        };
+
+        if multiline {
+            write!(f, "Part {}:", self.part)?;
+        } else {
+            write!(f, "Part {}: {answer}", self.part)?;
+        }
+        match self.previous {
+            None => {}
+            Some(Ok(previous)) if previous == answer => write!(f, " (unchanged)")?,
+            Some(Ok(previous)) if !multiline && !previous.to_string().contains('\n') => {
+                write!(f, " (was {previous})")?
+            }
+            Some(Ok(_)) => write!(f, " (changed)")?,
+            Some(Err(_)) => write!(f, " (was an error)")?,
+        }
+        match self.expected {
+            None => {}
+            Some(expected) if expected == answer => write!(f, ", right")?,
+            Some(expected) if !multiline && !expected.to_string().contains('\n') => {
+                write!(f, ", expected {expected}")?
+            }
+            Some(_) => write!(f, ", wrong")?,
+        }
+        writeln!(f)?;
+
+        if multiline {
+            // Show what is wrong with the answer, or else what changed.
+            let body = match (self.expected, previous) {
+                (Some(expected), _) if expected != answer => Diff(expected, answer).to_string(),
+                (_, Some(previous)) if previous != answer => Diff(previous, answer).to_string(),
+                _ => answer.to_string(),
+            };
+            for line in body.lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What to watch and how to run it.
+pub struct Watch {
+    pub day: u8,
+    pub dataset: String,
+    pub data_directory: PathBuf,
+    pub answers: PathBuf,
+}
+
+impl Watch {
+    /// The source of the day, its datasets and the known answers.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        vec![
+            PathBuf::from("src").join(format!("day{}.rs", self.day)),
+            self.data_directory.join(format!("day{}", self.day)),
+            self.answers.clone(),
+        ]
+    }
+
+    fn cargo(arguments: &[&str]) -> Result<Output, String> {
+        // Use the same cargo that runs this, if it does.
+        let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        process::Command::new(&cargo)
+            .args(arguments)
+            .output()
+            .map_err(|error| format!("Failed to run {cargo}: {error}"))
+    }
+
+    /// Run the tests of the day, returning how many passed, or what failed.
+    pub fn test(&self) -> Result<String, String> {
+        let filter = format!("day{}::", self.day);
+        let output = Self::cargo(&["test", "--quiet", "--color", "never", "--lib", &filter])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            return Ok(stdout
+                .lines()
+                .find_map(|line| line.strip_prefix("test result: ok. "))
+                .and_then(|result| result.split(';').next())
+                .unwrap_or("passed")
+                .to_string());
+        }
+        // Only keep the names of the tests that failed and why, or the
+        // compiler errors if it didn't build.
+        let mut summary = Vec::new();
+        let mut in_panic = false;
+        for line in stdout.lines() {
+            if let Some(test) = line.strip_prefix("---- ") {
+                summary.push(
+                    test.trim_end_matches(" ----")
+                        .trim_end_matches(" stdout")
+                        .to_string(),
+                );
+            } else if line.starts_with("thread '") {
+                in_panic = true;
+            } else if in_panic
+                && (line.is_empty() || line.starts_with("note:") || line == "stack backtrace:")
+            {
+                in_panic = false;
+            } else if in_panic {
+                summary.push(format!("  {line}"));
+            }
+        }
+        summary.extend(compiler_errors(&stderr));
+        Err(summary.join("\n"))
+    }
+
+    /// Rebuild and run the day on the dataset.
+    pub fn run(&self) -> Result<Vec<(Part, PartOutcome)>, String> {
+        let day = self.day.to_string();
+        let data_directory = self.data_directory.to_string_lossy();
+        let output = Self::cargo(&[
+            "run",
+            "--quiet",
+            "--color",
+            "never",
+            "--bin",
+            "aoc",
+            "--",
+            "--format",
+            "json",
+            "--data-dir",
+            &data_directory,
+            "run",
+            "--day",
+            &day,
+            "--dataset",
+            &self.dataset,
+        ])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let errors = compiler_errors(&stderr);
+            return Err(if errors.is_empty() {
+                stderr.trim().to_string()
+            } else {
+                errors.join("\n")
+            });
+        }
+        answers_from_json(&stdout)
+    }
+
+    /// Keep running the tests and the day whenever one of the watched files
+    /// changes, until stopped.
+    pub fn watch(&self) -> ! {
+        let mut previous: Vec<(Part, PartOutcome)> = Vec::new();
+        let mut snapshot = Snapshot::take(&self.paths());
+        println!("Day {}: watching for changes, stop with Ctrl-C.", self.day);
+        loop {
+            match self.test() {
+                Ok(passed) => println!("Tests: {passed}"),
+                Err(failures) => println!("Tests failed:\n{failures}"),
+            }
+
+            let answers = match Answers::load(&self.answers) {
+                Ok(answers) => answers,
+                Err(error) => {
+                    println!("{error}");
+                    Answers::default()
+                }
+            };
+            let run = self.run();
+            match &run {
+                Ok(parts) => {
+                    println!("Dataset {}:", self.dataset);
+                    for (part, current) in parts {
+                        let change = PartChange {
+                            part: *part,
+                            current,
+                            previous: previous
+                                .iter()
+                                .find(|(other, _)| other == part)
+                                .map(|(_, outcome)| outcome),
+                            expected: answers.get(self.day, &self.dataset, *part),
+                        };
+                        for line in change.to_string().lines() {
+                            println!("  {line}");
+                        }
+                    }
+                }
+                Err(error) => println!("Dataset {}: {error}", self.dataset),
+            }
+            if let Ok(parts) = run {
+                previous = parts;
+            }
+
+            // Wait for the next change.
+            loop {
+                thread::sleep(POLL_INTERVAL);
+                let next = Snapshot::take(&self.paths());
+                let changes = next.changes(&snapshot);
+                if !changes.is_empty() {
+                    println!();
+                    for path in changes {
+                        println!("Changed: {}", path.display());
+                    }
+                    snapshot = next;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_changes() {
+        let directory = env::temp_dir().join(format!("aoc2022-watch-{}", process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let source = directory.join("day1.rs");
+        fs::write(&source, "").unwrap();
+        let paths = [
+            source.clone(),
+            directory.join("data"),
+            directory.join("answers.toml"),
+        ];
+
+        let before = Snapshot::take(&paths);
+        assert!(Snapshot::take(&paths).changes(&before).is_empty());
+        fs::create_dir_all(directory.join("data")).unwrap();
+        fs::write(directory.join("data/example.txt"), "1").unwrap();
+        let after = Snapshot::take(&paths);
+        assert_eq!(after.changes(&before), [directory.join("data/example.txt")]);
+        fs::remove_file(&source).unwrap();
+        assert_eq!(Snapshot::take(&paths).changes(&after), [source.as_path()]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn part_changes() {
+        let change = |current: &PartOutcome, previous, expected| {
+            PartChange {
+                part: Part::One,
+                current,
+                previous,
+                expected,
+            }
+            .to_string()
+        };
+        let number = Ok(Answer::Number(1695));
+        assert_eq!(change(&number, None, None), "Part 1: 1695\n");
+        assert_eq!(
+            change(
+                &number,
+                Some(&Ok(Answer::Number(1690))),
+                Some(&Answer::Number(1695))
+            ),
+            "Part 1: 1695 (was 1690), right\n"
+        );
+        assert_eq!(
+            change(&number, Some(&number), Some(&Answer::Number(21))),
+            "Part 1: 1695 (unchanged), expected 21\n"
+        );
+        assert_eq!(
+            change(&Err("No marker.".to_string()), Some(&number), None),
+            "Part 1: error: No marker.\n"
+        );
+
+        let screen = Ok(Answer::Text("##..\n#.#.".to_string()));
+        assert_eq!(
+            change(
+                &screen,
+                Some(&Ok(Answer::Text("##..\n###.".to_string()))),
+                None
+            ),
+            "Part 1: (changed)\n      ##..\n    - ###.\n    + #.#.\n"
+        );
+    }
+
+    #[test]
+    fn answers_from_output() {
+        let output = r#"[{"day": 5, "input_hash": "0", "parse_ns": 1, "parts": [
+            {"part": 1, "answer": "CMZ", "time_ns": 1},
+            {"part": 2, "error": "Ran out of crates.", "time_ns": 1}]}]"#;
+        assert_eq!(
+            answers_from_json(output).unwrap(),
+            [
+                (Part::One, Ok(Answer::Text("CMZ".to_string()))),
+                (Part::Two, Err("Ran out of crates.".to_string()))
+            ]
+        );
+        assert_eq!(
+            answers_from_json(r#"[{"day": 5, "error": "No input."}]"#),
+            Err("No input.".to_string())
+        );
+    }
+}